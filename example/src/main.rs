@@ -1,9 +1,9 @@
 use hidapi::HidApi;
-use maschine::{get_device, Canvas, Colour, Controller, Event, EventContext, EventTask};
+use maschine::{get_any_device, Colour, Event, EventContext};
 
 fn main() {
     let hid_api = HidApi::new().unwrap();
-    let mut ctlr = get_device(&hid_api).unwrap();
+    let mut ctlr = get_any_device(&hid_api).unwrap();
 
     loop {
         // Allow controller to do work and update any events
@@ -11,17 +11,21 @@ fn main() {
         ctlr.tick(&mut context).unwrap();
 
         // Handle any generated events
-        while !context.events.is_empty() {
-            let event = context.events.pop_front().unwrap();
+        for event in context.drain() {
             match event {
                 Event::Button(button, pressed, shift) => {
                     if pressed {
                         ctlr.set_button_led(button, Colour::random());
                         println!("{:?}", button);
-                    } else if { !shift } {
+                    } else if !shift {
                         ctlr.set_button_led(button, Colour::BLACK);
                     }
                 }
+                Event::Encoder(encoder, direction, steps, _) => {
+                    println!("Encoder {} {:?} x{}", encoder, direction, steps);
+                }
+                Event::Pad(pad, _, _) => ctlr.set_pad_led(pad, Colour::random_indexed()),
+                Event::PadRelease(pad, _) => ctlr.set_pad_led(pad, Colour::BLACK),
                 _ => println!("{:?}", event),
             }
         }
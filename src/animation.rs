@@ -0,0 +1,278 @@
+use crate::colour::Colour;
+use crate::controller::Controller;
+use crate::display::MonochromeCanvas;
+use crate::events::Button;
+use std::f32::consts::TAU;
+use std::time::{Duration, Instant};
+
+///
+/// What happens when an animation reaches its last frame
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayMode {
+    /// Start again from the first frame
+    Loop,
+
+    /// Stop on the last frame
+    Once,
+}
+
+///
+/// Sequence of frames displayed for a fixed duration each
+///
+/// Call `tick` regularly (eg each time the controller is ticked) and copy any frame returned
+/// onto the device display with `copy_from`. Timing starts from the first call to `tick`.
+///
+pub struct Animation {
+    frames: Vec<MonochromeCanvas>,
+    frame_duration: Duration,
+    mode: PlayMode,
+    started_at: Option<Instant>,
+    current: Option<usize>,
+}
+
+impl Animation {
+    pub fn new(frames: Vec<MonochromeCanvas>, frame_duration: Duration, mode: PlayMode) -> Self {
+        Animation {
+            frames,
+            frame_duration,
+            mode,
+            started_at: None,
+            current: None,
+        }
+    }
+
+    ///
+    /// Advance the animation, returns the frame to display when it has changed
+    ///
+    pub fn tick(&mut self, now: Instant) -> Option<&MonochromeCanvas> {
+        if self.frames.is_empty() {
+            return None;
+        }
+
+        let started_at = *self.started_at.get_or_insert(now);
+        let elapsed = now.saturating_duration_since(started_at).as_nanos();
+        let step = (elapsed / self.frame_duration.as_nanos().max(1)) as usize;
+        let index = match self.mode {
+            PlayMode::Loop => step % self.frames.len(),
+            PlayMode::Once => step.min(self.frames.len() - 1),
+        };
+
+        if self.current == Some(index) {
+            return None;
+        }
+        self.current = Some(index);
+        self.frames.get(index)
+    }
+
+    ///
+    /// Restart the animation from the first frame on the next tick
+    ///
+    pub fn reset(&mut self) {
+        self.started_at = None;
+        self.current = None;
+    }
+
+    ///
+    /// A one-shot animation is showing its last frame, looping animations never finish
+    ///
+    pub fn is_finished(&self) -> bool {
+        self.mode == PlayMode::Once
+            && !self.frames.is_empty()
+            && self.current == Some(self.frames.len() - 1)
+    }
+
+    ///
+    /// Index of the frame last returned by `tick`
+    ///
+    pub fn current_frame(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+///
+/// Brightness curve applied by an `LedAnimator`
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedEffect {
+    /// Smoothly pulse between off and full brightness once per period
+    Breathe,
+
+    /// On for the first half of each period, off for the second
+    Blink,
+
+    /// Fade from full brightness to off over a single period
+    FadeOut,
+}
+
+///
+/// Animate a button LED with a timed brightness effect
+///
+/// Call `tick` regularly to apply the current colour with `set_button_led`. Timing starts from
+/// the first call to `tick`.
+///
+pub struct LedAnimator {
+    button: Button,
+    colour: Colour,
+    period: Duration,
+    effect: LedEffect,
+    started_at: Option<Instant>,
+}
+
+impl LedAnimator {
+    pub fn new(button: Button, colour: Colour, period: Duration, effect: LedEffect) -> Self {
+        LedAnimator {
+            button,
+            colour,
+            period,
+            effect,
+            started_at: None,
+        }
+    }
+
+    ///
+    /// Brightness (0..1) of the effect after `elapsed` time
+    ///
+    pub fn level(&self, elapsed: Duration) -> f32 {
+        let period = self.period.as_secs_f32();
+        if period <= 0.0 {
+            return match self.effect {
+                LedEffect::FadeOut => 0.0,
+                _ => 1.0,
+            };
+        }
+
+        let phase = (elapsed.as_secs_f32() / period).fract();
+        match self.effect {
+            LedEffect::Breathe => 0.5 - 0.5 * (phase * TAU).cos(),
+            LedEffect::Blink => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            LedEffect::FadeOut => (1.0 - elapsed.as_secs_f32() / period).max(0.0),
+        }
+    }
+
+    ///
+    /// Colour of the LED after `elapsed` time
+    ///
+    pub fn colour_at(&self, elapsed: Duration) -> Colour {
+        self.colour.scaled(self.level(elapsed))
+    }
+
+    ///
+    /// Apply the current colour to the button LED, returns the colour applied
+    ///
+    pub fn tick<C: Controller + ?Sized>(&mut self, controller: &mut C, now: Instant) -> Colour {
+        let started_at = *self.started_at.get_or_insert(now);
+        let colour = self.colour_at(now.saturating_duration_since(started_at));
+        controller.set_button_led(self.button, colour);
+        colour
+    }
+
+    ///
+    /// Restart the effect on the next tick
+    ///
+    pub fn reset(&mut self) {
+        self.started_at = None;
+    }
+
+    ///
+    /// A fade out has completed, other effects repeat indefinitely
+    ///
+    pub fn is_finished(&self, now: Instant) -> bool {
+        self.effect == LedEffect::FadeOut
+            && self
+                .started_at
+                .is_some_and(|started_at| now.saturating_duration_since(started_at) >= self.period)
+    }
+
+    pub fn button(&self) -> Button {
+        self.button
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devices::MockController;
+    use crate::display::{Canvas, Pixel};
+
+    fn frames() -> Vec<MonochromeCanvas> {
+        (0..3)
+            .map(|idx| {
+                let mut frame = MonochromeCanvas::new(8, 8);
+                frame.set_pixel(idx, 0, Pixel::On);
+                frame
+            })
+            .collect()
+    }
+
+    #[test]
+    fn loop_returns_changed_frames() {
+        let start = Instant::now();
+        let frame = Duration::from_millis(100);
+        let mut animation = Animation::new(frames(), frame, PlayMode::Loop);
+
+        assert_eq!(animation.tick(start).unwrap().data()[0], 0x01);
+        assert!(animation.tick(start + frame / 2).is_none());
+        assert_eq!(animation.tick(start + frame).unwrap().data()[1], 0x01);
+        assert_eq!(animation.tick(start + frame * 3).unwrap().data()[0], 0x01);
+        assert_eq!(animation.current_frame(), Some(0));
+        assert!(!animation.is_finished());
+    }
+
+    #[test]
+    fn once_stops_on_last_frame() {
+        let start = Instant::now();
+        let frame = Duration::from_millis(100);
+        let mut animation = Animation::new(frames(), frame, PlayMode::Once);
+
+        animation.tick(start);
+        assert_eq!(animation.tick(start + frame * 10).unwrap().data()[2], 0x01);
+        assert!(animation.tick(start + frame * 20).is_none());
+        assert!(animation.is_finished());
+
+        animation.reset();
+        assert_eq!(animation.tick(start + frame * 30).unwrap().data()[0], 0x01);
+    }
+
+    #[test]
+    fn led_effect_levels() {
+        let period = Duration::from_millis(1000);
+        let breathe = LedAnimator::new(Button::Play, Colour::WHITE, period, LedEffect::Breathe);
+        assert_eq!(breathe.colour_at(Duration::ZERO), Colour::BLACK);
+        assert_eq!(breathe.colour_at(period / 2), Colour::WHITE);
+
+        let blink = LedAnimator::new(Button::Play, Colour::RED, period, LedEffect::Blink);
+        assert_eq!(blink.colour_at(period / 4), Colour::RED);
+        assert_eq!(blink.colour_at(period * 3 / 4), Colour::BLACK);
+
+        let fade = LedAnimator::new(Button::Play, Colour::WHITE, period, LedEffect::FadeOut);
+        assert_eq!(fade.colour_at(Duration::ZERO), Colour::WHITE);
+        assert_eq!(fade.colour_at(period * 2), Colour::BLACK);
+    }
+
+    #[test]
+    fn led_animator_applies_colour() {
+        let start = Instant::now();
+        let period = Duration::from_millis(100);
+        let mut controller = MockController::new();
+        let mut fade = LedAnimator::new(Button::Rec, Colour::RED, period, LedEffect::FadeOut);
+
+        assert_eq!(fade.tick(&mut controller, start), Colour::RED);
+        assert_eq!(controller.button_led(Button::Rec), Colour::RED);
+        assert!(!fade.is_finished(start));
+
+        fade.tick(&mut controller, start + period);
+        assert_eq!(controller.button_led(Button::Rec), Colour::BLACK);
+        assert!(fade.is_finished(start + period));
+    }
+}
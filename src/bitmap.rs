@@ -0,0 +1,800 @@
+//!
+//! # Cut down bitmap loader
+//!
+//! Only supports Uncompressed 1, 4, 8 (palette indexed), 24 and 32bpp and RLE4/RLE8 compressed
+//! version 3 (BITMAPINFOHEADER) and version 4 bitmaps
+//!
+//! Will ignore colours space info and inverts the pixel buffer to set the origin to the top left.
+
+pub use crate::image::Image;
+
+use crate::colour::Colour;
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::fmt::Formatter;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+///
+/// Potential errors loading images
+///
+#[derive(Debug)]
+pub enum Error {
+    IOError(Box<std::io::Error>), // Wrap io::Error
+    InvalidSignature,             // Bitmap signature is invalid or unsupported
+    UnsupportedFileVersion,       // Invalid or Unsupported bitmap format version
+    UnsupportedCompressionMethod, // Invalid or Unsupported compression method
+    UnsupportedBitDepth,          // Invalid or Unsupported compression method
+    UnsupportedColourType,        // Invalid or Unsupported colour type (PNG)
+    InvalidHeader,                // Header dimensions or sizes do not match the file
+    #[cfg(feature = "png")]
+    PngDecoding(Box<::png::DecodingError>), // Wrap png::DecodingError
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        Error::IOError(Box::new(err))
+    }
+}
+
+#[derive(Debug)]
+pub enum BitmapVersion {
+    Three,
+    Four,
+}
+
+impl BitmapVersion {
+    ///
+    /// Size of the DIB header in bytes
+    ///
+    pub fn header_size(&self) -> u32 {
+        match self {
+            BitmapVersion::Three => 40,
+            BitmapVersion::Four => 108,
+        }
+    }
+
+    pub fn from_u32(v: u32) -> Result<BitmapVersion, Error> {
+        Ok(match v {
+            40 => BitmapVersion::Three,
+            108 => BitmapVersion::Four,
+            _ => return Err(Error::UnsupportedFileVersion),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum CompressionMethod {
+    None,
+    Rle8,
+    Rle4,
+}
+
+impl CompressionMethod {
+    pub fn from_u32(v: u32) -> Result<CompressionMethod, Error> {
+        Ok(match v {
+            0 => CompressionMethod::None,
+            1 => CompressionMethod::Rle8,
+            2 => CompressionMethod::Rle4,
+            _ => return Err(Error::UnsupportedCompressionMethod),
+        })
+    }
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct FileHeader {
+    file_size: u32,
+    // reserved: [u16; 2],
+    pixel_data_offset: u32,
+}
+
+impl FileHeader {
+    fn read(reader: &mut Cursor<Vec<u8>>) -> Result<Self, Error> {
+        let file_size = reader.read_u32::<LittleEndian>()?;
+        reader.read_u32::<LittleEndian>()?;
+        Ok(FileHeader {
+            file_size,
+            pixel_data_offset: reader.read_u32::<LittleEndian>()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+pub struct DIBHeader {
+    version: BitmapVersion,
+    width: i32,
+    height: i32,
+    planes: u16,
+    bits_per_pixel: u16,
+    compression: CompressionMethod,
+    data_size: u32,
+    x_ppm: u32,
+    y_ppm: u32,
+    colour_count: u32,
+    important_colour_count: u32,
+}
+
+impl DIBHeader {
+    fn read(reader: &mut Cursor<Vec<u8>>) -> Result<Self, Error> {
+        Ok(DIBHeader {
+            version: BitmapVersion::from_u32(reader.read_u32::<LittleEndian>()?)?,
+            width: reader.read_i32::<LittleEndian>()?,
+            height: reader.read_i32::<LittleEndian>()?,
+            planes: reader.read_u16::<LittleEndian>()?,
+            bits_per_pixel: reader.read_u16::<LittleEndian>()?,
+            compression: CompressionMethod::from_u32(reader.read_u32::<LittleEndian>()?)?,
+            data_size: reader.read_u32::<LittleEndian>()?,
+            x_ppm: reader.read_u32::<LittleEndian>()?,
+            y_ppm: reader.read_u32::<LittleEndian>()?,
+            colour_count: reader.read_u32::<LittleEndian>()?,
+            important_colour_count: reader.read_u32::<LittleEndian>()?,
+        })
+    }
+
+    ///
+    /// Number of pixels in the bitmap
+    ///
+    fn pixel_data_size(&self) -> Result<usize, Error> {
+        (self.width.unsigned_abs() as usize)
+            .checked_mul(self.height.unsigned_abs() as usize)
+            .ok_or(Error::InvalidHeader)
+    }
+
+    ///
+    /// Size of a row of pixel data, rows are padded to a multiple of 4 bytes
+    ///
+    fn row_size(&self) -> Result<usize, Error> {
+        (self.width.unsigned_abs() as usize)
+            .checked_mul(self.bits_per_pixel as usize)
+            .and_then(|bits| bits.div_ceil(32).checked_mul(4))
+            .ok_or(Error::InvalidHeader)
+    }
+
+    ///
+    /// Number of entries in the colour table (palette indexed bit depths only)
+    ///
+    fn palette_size(&self) -> usize {
+        match (self.bits_per_pixel, self.colour_count) {
+            (1 | 4 | 8, 0) => 1 << self.bits_per_pixel,
+            (1 | 4 | 8, count) => (count as usize).min(1 << self.bits_per_pixel),
+            _ => 0,
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub struct Bitmap {
+    file_header: FileHeader,
+    pub dib_header: DIBHeader,
+    pixel_buffer: Vec<Colour>,
+}
+
+impl Bitmap {
+    pub fn read_from_buffer(buffer: Vec<u8>) -> Result<Self, Error> {
+        let mut reader = Cursor::new(buffer);
+
+        check_signature(&mut reader)?;
+        let file_header = FileHeader::read(&mut reader)?;
+        let dib_header = DIBHeader::read(&mut reader)?;
+
+        // Check on supported bbp are in this file.
+        match (&dib_header.compression, dib_header.bits_per_pixel) {
+            (CompressionMethod::None, 1 | 4 | 8 | 24 | 32) => (),
+            (CompressionMethod::Rle8, 8) | (CompressionMethod::Rle4, 4) => (),
+            _ => return Err(Error::UnsupportedBitDepth),
+        }
+
+        let palette = read_palette(&mut reader, &dib_header)?;
+        let offset = file_header.pixel_data_offset;
+        let mut data = match dib_header.compression {
+            CompressionMethod::None => read_pixel_data(&mut reader, offset, &dib_header, &palette)?,
+            CompressionMethod::Rle8 | CompressionMethod::Rle4 => {
+                read_rle_pixel_data(&mut reader, offset, &dib_header, &palette)?
+            }
+        };
+
+        // Rows are stored bottom-up unless the height is negative, reorder so the origin is
+        // top-left
+        let width = dib_header.width.unsigned_abs() as usize;
+        if dib_header.height > 0 && width > 0 {
+            data = data.chunks(width).rev().flatten().copied().collect();
+        }
+
+        Ok(Bitmap {
+            file_header,
+            dib_header,
+            pixel_buffer: data,
+        })
+    }
+
+    ///
+    /// All pixels of the bitmap, rows are in order from the top-left origin
+    ///
+    pub fn pixels(&self) -> &[Colour] {
+        &self.pixel_buffer
+    }
+
+    ///
+    /// Pixels of a single row, rows are numbered from the top of the bitmap
+    ///
+    pub fn row(&self, y: usize) -> &[Colour] {
+        let width = self.width();
+        &self.pixel_buffer[(y * width)..((y + 1) * width)]
+    }
+
+    ///
+    /// Copy a region of the bitmap into a new bitmap, the region is clipped to the bitmap
+    ///
+    pub fn crop(&self, x: usize, y: usize, w: usize, h: usize) -> Bitmap {
+        let w = w.min(self.width().saturating_sub(x));
+        let h = h.min(self.height().saturating_sub(y));
+        Bitmap::from_fn(w, h, |px, py| self.pixel(x + px, y + py))
+    }
+
+    ///
+    /// Resize the bitmap to a new width and height using nearest neighbour sampling
+    ///
+    pub fn scale_nearest(&self, w: usize, h: usize) -> Bitmap {
+        // An empty bitmap has nothing to sample
+        let (w, h) = if self.width() == 0 || self.height() == 0 {
+            (0, 0)
+        } else {
+            (w, h)
+        };
+
+        Bitmap::from_fn(w, h, |px, py| {
+            self.pixel(px * self.width() / w, py * self.height() / h)
+        })
+    }
+
+    ///
+    /// Build a 24bpp bitmap from a function returning the colour of each pixel
+    ///
+    fn from_fn<F: Fn(usize, usize) -> Colour>(width: usize, height: usize, f: F) -> Bitmap {
+        let mut pixel_buffer = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                pixel_buffer.push(f(x, y));
+            }
+        }
+
+        Bitmap {
+            file_header: FileHeader {
+                file_size: 0,
+                pixel_data_offset: 0,
+            },
+            dib_header: DIBHeader {
+                version: BitmapVersion::Three,
+                width: width as i32,
+                height: height as i32,
+                planes: 1,
+                bits_per_pixel: 24,
+                compression: CompressionMethod::None,
+                data_size: 0,
+                x_ppm: 0,
+                y_ppm: 0,
+                colour_count: 0,
+                important_colour_count: 0,
+            },
+            pixel_buffer,
+        }
+    }
+}
+
+impl Image for Bitmap {
+    fn width(&self) -> usize {
+        self.dib_header.width.unsigned_abs() as usize
+    }
+
+    fn height(&self) -> usize {
+        self.dib_header.height.unsigned_abs() as usize
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> Colour {
+        self.pixel_buffer[(y * self.width()) + x]
+    }
+}
+
+impl std::fmt::Display for Bitmap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Width: {}\nHeight: {}\nBitDepth: {}bpp",
+            self.width(),
+            self.height(),
+            self.dib_header.bits_per_pixel
+        )
+    }
+}
+
+const SIGNATURE: u16 = 0x4D_42;
+
+///
+/// Check for the Bitmap signature value
+///
+fn check_signature(reader: &mut Cursor<Vec<u8>>) -> Result<(), Error> {
+    let signature = reader.read_u16::<LittleEndian>()?;
+    if signature == SIGNATURE {
+        Ok(())
+    } else {
+        Err(Error::InvalidSignature)
+    }
+}
+
+///
+/// Read in the colour table that follows the DIB header
+///
+fn read_palette(
+    reader: &mut Cursor<Vec<u8>>,
+    dib_header: &DIBHeader,
+) -> Result<Vec<Colour>, Error> {
+    let size = dib_header.palette_size();
+    let mut palette: Vec<Colour> = Vec::with_capacity(size);
+
+    reader.seek(SeekFrom::Start(
+        14 + dib_header.version.header_size() as u64,
+    ))?;
+
+    let mut entry = [0u8; 4];
+    for _ in 0..size {
+        reader.read_exact(&mut entry)?;
+        palette.push(Colour::new(entry[2], entry[1], entry[0]))
+    }
+
+    Ok(palette)
+}
+
+///
+/// Read in the pixel data, palette indexed pixels are expanded to colours
+///
+fn read_pixel_data(
+    reader: &mut Cursor<Vec<u8>>,
+    offset: u32,
+    dib_header: &DIBHeader,
+    palette: &[Colour],
+) -> Result<Vec<Colour>, Error> {
+    // Check the pixel data is within the file before allocating any buffers
+    let row_size = dib_header.row_size()?;
+    let data_size = row_size
+        .checked_mul(dib_header.height.unsigned_abs() as usize)
+        .ok_or(Error::InvalidHeader)?;
+    let available = reader.get_ref().len().saturating_sub(offset as usize);
+    if data_size > available {
+        return Err(Error::InvalidHeader);
+    }
+
+    let mut data: Vec<Colour> = Vec::with_capacity(dib_header.pixel_data_size()?);
+
+    reader.seek(SeekFrom::Start(offset as u64))?;
+
+    let width = dib_header.width.unsigned_abs() as usize;
+    let bpp = dib_header.bits_per_pixel as usize;
+    let mut row = vec![0u8; row_size];
+    for _ in 0..dib_header.height.unsigned_abs() {
+        reader.read_exact(&mut row)?;
+        for x in 0..width {
+            let colour = if bpp >= 24 {
+                // BGR(A), any alpha channel is ignored
+                let offset = x * (bpp / 8);
+                Colour::new(row[offset + 2], row[offset + 1], row[offset])
+            } else {
+                // Indexes are packed from the most significant bits of each byte
+                let bit = x * bpp;
+                let index = (row[bit / 8] >> (8 - bpp - (bit % 8))) & ((1u16 << bpp) - 1) as u8;
+                palette
+                    .get(index as usize)
+                    .copied()
+                    .unwrap_or(Colour::BLACK)
+            };
+            data.push(colour);
+        }
+    }
+
+    Ok(data)
+}
+
+// Largest RLE bitmap that is decoded (4096 x 4096)
+const MAX_RLE_PIXELS: usize = 1 << 24;
+
+///
+/// Read in run-length encoded pixel data (RLE8 or RLE4), pixels not covered by the encoded
+/// data are set to the first palette entry
+///
+/// Escapes can skip any number of pixels so the size of the encoded data does not bound the
+/// dimensions, bitmaps with more than `MAX_RLE_PIXELS` pixels are rejected before the pixel
+/// buffer is allocated.
+///
+fn read_rle_pixel_data(
+    reader: &mut Cursor<Vec<u8>>,
+    offset: u32,
+    dib_header: &DIBHeader,
+    palette: &[Colour],
+) -> Result<Vec<Colour>, Error> {
+    let width = dib_header.width.unsigned_abs() as usize;
+    let height = dib_header.height.unsigned_abs() as usize;
+    let rle4 = matches!(dib_header.compression, CompressionMethod::Rle4);
+
+    let available = reader.get_ref().len().saturating_sub(offset as usize);
+    let pixel_count = dib_header.pixel_data_size()?;
+    if pixel_count > MAX_RLE_PIXELS || dib_header.data_size as usize > available {
+        return Err(Error::InvalidHeader);
+    }
+    let mut indexes = vec![0u8; pixel_count];
+
+    reader.seek(SeekFrom::Start(offset as u64))?;
+
+    // Index of the nth pixel of a run, RLE4 alternates between the high and low nibble
+    let nth_index = |value: u8, n: usize| {
+        if rle4 {
+            (value >> (4 - (n % 2) * 4)) & 0x0F
+        } else {
+            value
+        }
+    };
+
+    let (mut x, mut y) = (0usize, 0usize);
+    loop {
+        let count = reader.read_u8()?;
+        let value = reader.read_u8()?;
+        match (count, value) {
+            // End of line
+            (0, 0) => {
+                x = 0;
+                y += 1;
+            }
+            // End of bitmap
+            (0, 1) => break,
+            // Delta
+            (0, 2) => {
+                x += reader.read_u8()? as usize;
+                y += reader.read_u8()? as usize;
+            }
+            // Absolute mode, data is padded to a 16bit boundary
+            (0, count) => {
+                let count = count as usize;
+                let size = if rle4 { count.div_ceil(2) } else { count };
+                let mut data = vec![0u8; size + (size % 2)];
+                reader.read_exact(&mut data)?;
+                for n in 0..count {
+                    if x < width && y < height {
+                        let byte = if rle4 { data[n / 2] } else { data[n] };
+                        indexes[(y * width) + x] = nth_index(byte, n);
+                    }
+                    x += 1;
+                }
+            }
+            // Encoded run
+            (count, value) => {
+                for n in 0..count as usize {
+                    if x < width && y < height {
+                        indexes[(y * width) + x] = nth_index(value, n);
+                    }
+                    x += 1;
+                }
+            }
+        }
+    }
+
+    let background = palette.first().copied().unwrap_or(Colour::BLACK);
+    Ok(indexes
+        .iter()
+        .map(|index| palette.get(*index as usize).copied().unwrap_or(background))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Colour = Colour::BLACK;
+    const WHITE: Colour = Colour::WHITE;
+
+    ///
+    /// Build a version 4 bitmap file from a palette and (bottom-up, padded) pixel data
+    ///
+    fn build_bitmap(
+        width: i32,
+        height: i32,
+        bpp: u16,
+        palette: &[[u8; 4]],
+        pixels: &[u8],
+    ) -> Vec<u8> {
+        build_bitmap_with_header(108, width, height, bpp, palette, pixels)
+    }
+
+    fn build_bitmap_with_header(
+        header_size: u32,
+        width: i32,
+        height: i32,
+        bpp: u16,
+        palette: &[[u8; 4]],
+        pixels: &[u8],
+    ) -> Vec<u8> {
+        let offset = 14 + header_size + (palette.len() as u32 * 4);
+        let mut buffer = vec![];
+        buffer.extend_from_slice(&SIGNATURE.to_le_bytes());
+        buffer.extend_from_slice(&(offset + pixels.len() as u32).to_le_bytes());
+        buffer.extend_from_slice(&[0; 4]);
+        buffer.extend_from_slice(&offset.to_le_bytes());
+        buffer.extend_from_slice(&header_size.to_le_bytes());
+        buffer.extend_from_slice(&width.to_le_bytes());
+        buffer.extend_from_slice(&height.to_le_bytes());
+        buffer.extend_from_slice(&1u16.to_le_bytes());
+        buffer.extend_from_slice(&bpp.to_le_bytes());
+        buffer.extend_from_slice(&0u32.to_le_bytes());
+        buffer.extend_from_slice(&(pixels.len() as u32).to_le_bytes());
+        buffer.extend_from_slice(&[0; 8]);
+        buffer.extend_from_slice(&(palette.len() as u32).to_le_bytes());
+        buffer.extend_from_slice(&[0; 4]);
+        buffer.resize(14 + header_size as usize, 0);
+        for entry in palette {
+            buffer.extend_from_slice(entry);
+        }
+        buffer.extend_from_slice(pixels);
+        buffer
+    }
+
+    #[test]
+    fn read_1bpp() {
+        // Rows are stored bottom-up, each padded to 4 bytes
+        let buffer = build_bitmap(
+            10,
+            2,
+            1,
+            &[[0, 0, 0, 0], [0xFF, 0xFF, 0xFF, 0]],
+            &[
+                0b1000_0000,
+                0b0100_0000,
+                0,
+                0,
+                0b0111_1111,
+                0b1000_0000,
+                0,
+                0,
+            ],
+        );
+        let bmp = Bitmap::read_from_buffer(buffer).unwrap();
+
+        assert_eq!(bmp.pixel(0, 0), BLACK);
+        assert_eq!(bmp.pixel(1, 0), WHITE);
+        assert_eq!(bmp.pixel(8, 0), WHITE);
+        assert_eq!(bmp.pixel(9, 0), BLACK);
+        assert_eq!(bmp.pixel(0, 1), WHITE);
+        assert_eq!(bmp.pixel(1, 1), BLACK);
+        assert_eq!(bmp.pixel(9, 1), WHITE);
+    }
+
+    #[test]
+    fn read_4bpp() {
+        let red = [0, 0, 0xFF, 0];
+        let buffer = build_bitmap(3, 1, 4, &[[0, 0, 0, 0], red], &[0x01, 0x00, 0, 0]);
+        let bmp = Bitmap::read_from_buffer(buffer).unwrap();
+
+        assert_eq!(bmp.pixel(0, 0), BLACK);
+        assert_eq!(bmp.pixel(1, 0).components().0, 0xFF);
+        assert_eq!(bmp.pixel(2, 0), BLACK);
+    }
+
+    #[test]
+    fn read_8bpp() {
+        let grey = [0x80, 0x80, 0x80, 0];
+        let buffer = build_bitmap(2, 1, 8, &[[0, 0, 0, 0], grey], &[1, 0, 0, 0]);
+        let bmp = Bitmap::read_from_buffer(buffer).unwrap();
+
+        assert_eq!(bmp.pixel(0, 0).luminance(), 0x80);
+        assert_eq!(bmp.pixel(1, 0), BLACK);
+    }
+
+    #[test]
+    fn read_24bpp() {
+        let buffer = build_bitmap(1, 1, 24, &[], &[0x00, 0x00, 0xFF, 0]);
+        let bmp = Bitmap::read_from_buffer(buffer).unwrap();
+
+        assert_eq!(bmp.pixel(0, 0).components().0, 0xFF);
+        assert_eq!(bmp.pixel(0, 0).components().2, 0x00);
+    }
+
+    #[test]
+    fn read_32bpp() {
+        // Rows of 32bpp pixels need no padding
+        let buffer = build_bitmap(
+            2,
+            2,
+            32,
+            &[],
+            &[
+                0xFF, 0x00, 0x00, 0x80, 0x00, 0xFF, 0x00, 0xFF, // Bottom row
+                0x00, 0x00, 0xFF, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, // Top row
+            ],
+        );
+        let bmp = Bitmap::read_from_buffer(buffer).unwrap();
+
+        assert_eq!(bmp.pixel(0, 0).components().0, 0xFF);
+        assert_eq!(bmp.pixel(1, 0), WHITE);
+        assert_eq!(bmp.pixel(0, 1).components().2, 0xFF);
+        assert_eq!(bmp.pixel(1, 1).components().1, 0xFF);
+        assert_eq!(bmp.pixel(1, 1).components().0, 0x00);
+    }
+
+    #[test]
+    fn read_rle8() {
+        let palette = [[0, 0, 0, 0], [0xFF, 0xFF, 0xFF, 0], [0, 0, 0xFF, 0]];
+        let mut buffer = build_bitmap(
+            4,
+            2,
+            8,
+            &palette,
+            &[
+                0x03, 0x01, 0x00, 0x00, // Bottom row, run of 3 white then end of line
+                0x00, 0x03, 0x02, 0x01, 0x02, 0x00, // Top row, absolute red, white, red
+                0x00, 0x01, // End of bitmap
+            ],
+        );
+        buffer[30] = 1; // Compression method
+
+        let bmp = Bitmap::read_from_buffer(buffer).unwrap();
+
+        assert_eq!(bmp.pixel(0, 1), WHITE);
+        assert_eq!(bmp.pixel(2, 1), WHITE);
+        assert_eq!(bmp.pixel(3, 1), BLACK);
+        assert_eq!(bmp.pixel(0, 0).components().0, 0xFF);
+        assert_eq!(bmp.pixel(0, 0).components().1, 0x00);
+        assert_eq!(bmp.pixel(1, 0), WHITE);
+        assert_eq!(bmp.pixel(2, 0).components().0, 0xFF);
+        assert_eq!(bmp.pixel(3, 0), BLACK);
+    }
+
+    #[test]
+    fn read_rle4() {
+        let palette = [[0, 0, 0, 0], [0xFF, 0xFF, 0xFF, 0]];
+        let mut buffer = build_bitmap(
+            4,
+            1,
+            4,
+            &palette,
+            &[
+                0x03, 0x10, // Run of 3 alternating white and black
+                0x00, 0x01, // End of bitmap
+            ],
+        );
+        buffer[30] = 2; // Compression method
+
+        let bmp = Bitmap::read_from_buffer(buffer).unwrap();
+
+        assert_eq!(bmp.pixel(0, 0), WHITE);
+        assert_eq!(bmp.pixel(1, 0), BLACK);
+        assert_eq!(bmp.pixel(2, 0), WHITE);
+        assert_eq!(bmp.pixel(3, 0), BLACK);
+    }
+
+    #[test]
+    fn read_version_3() {
+        let buffer = build_bitmap_with_header(
+            40,
+            2,
+            1,
+            8,
+            &[[0, 0, 0, 0], [0xFF, 0xFF, 0xFF, 0]],
+            &[1, 0, 0, 0],
+        );
+        let bmp = Bitmap::read_from_buffer(buffer).unwrap();
+
+        assert!(matches!(bmp.dib_header.version, BitmapVersion::Three));
+        assert_eq!(bmp.pixel(0, 0), WHITE);
+        assert_eq!(bmp.pixel(1, 0), BLACK);
+    }
+
+    #[test]
+    fn crop_and_scale() {
+        // 2x2 24bpp image, bottom row first: black, white / white, black
+        let buffer = build_bitmap(
+            2,
+            2,
+            24,
+            &[],
+            &[
+                0, 0, 0, 0xFF, 0xFF, 0xFF, 0, 0, //
+                0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 0,
+            ],
+        );
+        let bitmap = Bitmap::read_from_buffer(buffer).unwrap();
+
+        let cropped = bitmap.crop(1, 0, 5, 5);
+        assert_eq!((cropped.width(), cropped.height()), (1, 2));
+        assert_eq!(cropped.pixel(0, 0), BLACK);
+        assert_eq!(cropped.pixel(0, 1), WHITE);
+
+        let scaled = bitmap.scale_nearest(4, 4);
+        assert_eq!((scaled.width(), scaled.height()), (4, 4));
+        assert_eq!(scaled.pixel(0, 0), WHITE);
+        assert_eq!(scaled.pixel(1, 1), WHITE);
+        assert_eq!(scaled.pixel(2, 1), BLACK);
+        assert_eq!(scaled.pixel(3, 3), WHITE);
+
+        let scaled = bitmap.scale_nearest(1, 1);
+        assert_eq!(scaled.pixel(0, 0), WHITE);
+    }
+
+    #[test]
+    fn pixels_and_rows_are_top_down() {
+        // 1x2 24bpp image, bottom row first: black then white
+        let buffer = build_bitmap(1, 2, 24, &[], &[0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0]);
+        let bitmap = Bitmap::read_from_buffer(buffer).unwrap();
+
+        assert_eq!(bitmap.pixels(), &[WHITE, BLACK]);
+        assert_eq!(bitmap.row(0), &[WHITE]);
+        assert_eq!(bitmap.row(1), &[BLACK]);
+
+        // Negative heights are stored top-down
+        let buffer = build_bitmap(1, -2, 24, &[], &[0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0]);
+        let bitmap = Bitmap::read_from_buffer(buffer).unwrap();
+        assert_eq!(bitmap.pixels(), &[BLACK, WHITE]);
+    }
+
+    #[test]
+    fn reject_dimensions_that_overflow() {
+        let buffer = build_bitmap(i32::MAX, i32::MIN, 32, &[], &[0; 4]);
+        assert!(matches!(
+            Bitmap::read_from_buffer(buffer),
+            Err(Error::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn reject_pixel_data_larger_than_file() {
+        let buffer = build_bitmap(1000, 1000, 24, &[], &[0; 4]);
+        assert!(matches!(
+            Bitmap::read_from_buffer(buffer),
+            Err(Error::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn palette_is_capped_by_bit_depth() {
+        let mut buffer = build_bitmap(
+            2,
+            1,
+            1,
+            &[[0, 0, 0, 0], [0xFF, 0xFF, 0xFF, 0]],
+            &[0b0100_0000, 0, 0, 0],
+        );
+        // Colour count
+        buffer[46..50].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let bmp = Bitmap::read_from_buffer(buffer).unwrap();
+        assert_eq!(bmp.pixel(0, 0), BLACK);
+        assert_eq!(bmp.pixel(1, 0), WHITE);
+    }
+
+    #[test]
+    fn reject_rle_dimensions_too_large() {
+        let palette = [[0, 0, 0, 0], [0xFF, 0xFF, 0xFF, 0]];
+        let mut buffer = build_bitmap(100_000, 100_000, 8, &palette, &[0x00, 0x01]);
+        buffer[30] = 1; // Compression method
+        assert!(matches!(
+            Bitmap::read_from_buffer(buffer),
+            Err(Error::InvalidHeader)
+        ));
+
+        // Data size claims more data than the file holds
+        let mut buffer = build_bitmap(2, 1, 8, &palette, &[0x02, 0x01, 0x00, 0x01]);
+        buffer[30] = 1; // Compression method
+        buffer[34..38].copy_from_slice(&64u32.to_le_bytes());
+        assert!(matches!(
+            Bitmap::read_from_buffer(buffer),
+            Err(Error::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn read_rle8_end_of_bitmap_only() {
+        // Pixels not covered by the encoded data are the first palette entry
+        let palette = [[0xFF, 0xFF, 0xFF, 0], [0, 0, 0, 0]];
+        let mut buffer = build_bitmap(300, 2, 8, &palette, &[0x00, 0x01]);
+        buffer[30] = 1; // Compression method
+
+        let bmp = Bitmap::read_from_buffer(buffer).unwrap();
+        assert_eq!((bmp.width(), bmp.height()), (300, 2));
+        assert!(bmp.pixels().iter().all(|colour| *colour == WHITE));
+    }
+}
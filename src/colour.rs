@@ -1,3 +1,4 @@
+use crate::error::Error;
 use rand::{thread_rng, Rng};
 
 const COLOURS: [Colour; 6] = [
@@ -38,7 +39,7 @@ const COLOURS: [Colour; 6] = [
 ///
 /// Can represent RGB or Mono colours
 ///
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug, Hash)]
 pub struct Colour {
     r: u8,
     g: u8,
@@ -76,12 +77,41 @@ impl Colour {
         }
     }
 
+    /// Pick a random colour from the primary palette (see `Palette::primary`)
     pub fn random_indexed() -> Self {
         let mut rng = rand::thread_rng();
         COLOURS[rng.gen_range(0..6)]
     }
 
+    /// Construct a colour from hue (degrees 0..360), saturation (0..1) and value (0..1)
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Self {
+        let h = h.rem_euclid(360.0);
+        let s = s.clamp(0.0, 1.0);
+        let v = v.clamp(0.0, 1.0);
+
+        let c = v * s;
+        let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+        let m = v - c;
+        let (r, g, b) = match (h / 60.0) as u8 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+
+        Self {
+            r: ((r + m) * 255.0).round() as u8,
+            g: ((g + m) * 255.0).round() as u8,
+            b: ((b + m) * 255.0).round() as u8,
+        }
+    }
+
     /// Construct a colour from 24bit number
+    ///
+    /// Red is read from the low byte (0xBBGGRR), see `from_rgb_u32` for the conventional
+    /// 0xRRGGBB ordering.
     pub fn from_u24(v: u32) -> Self {
         let r = (v & 0xFF) as u8;
         let g = ((v >> 8) & 0xFF) as u8;
@@ -89,6 +119,41 @@ impl Colour {
         Self { r, g, b }
     }
 
+    /// Construct a colour from a 0xRRGGBB number (any upper bits are ignored)
+    pub fn from_rgb_u32(v: u32) -> Self {
+        let r = ((v >> 16) & 0xFF) as u8;
+        let g = ((v >> 8) & 0xFF) as u8;
+        let b = (v & 0xFF) as u8;
+        Self { r, g, b }
+    }
+
+    /// Parse a colour from a "#RRGGBB", "RRGGBB" or "#RGB" hex string
+    pub fn from_hex(s: &str) -> Result<Self, Error> {
+        let digits = s.strip_prefix('#').unwrap_or(s);
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::InvalidColour);
+        }
+
+        let channel = |idx: usize, len: usize| {
+            u8::from_str_radix(&digits[idx * len..(idx + 1) * len], 16)
+                .map(|v| if len == 1 { v * 0x11 } else { v })
+                .map_err(|_| Error::InvalidColour)
+        };
+
+        match digits.len() {
+            6 => Ok(Self::new(channel(0, 2)?, channel(1, 2)?, channel(2, 2)?)),
+            3 if s.starts_with('#') => {
+                Ok(Self::new(channel(0, 1)?, channel(1, 1)?, channel(2, 1)?))
+            }
+            _ => Err(Error::InvalidColour),
+        }
+    }
+
+    /// Format the colour as a "#RRGGBB" hex string
+    pub fn to_hex(&self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+
     /// "Monochrome" representation of the colour
     pub fn as_1bit(&self) -> u8 {
         if (self.r > 0x7F) | (self.g > 0x7F) | (self.b > 0x7F) {
@@ -98,16 +163,357 @@ impl Colour {
         }
     }
 
+    /// "Monochrome" representation of the colour thresholded on perceived brightness
+    /// (luminance), unlike `as_1bit` a saturated blue is off
+    pub fn as_1bit_luminance(&self) -> u8 {
+        if self.luminance() > 0x7F {
+            0xFF
+        } else {
+            0x00
+        }
+    }
+
+    /// Perceived brightness of the colour (Rec. 601 luma weighting)
+    pub fn luminance(&self) -> u8 {
+        ((299 * self.r as u32 + 587 * self.g as u32 + 114 * self.b as u32) / 1000) as u8
+    }
+
+    /// Grey of the same luminance as the colour
+    pub fn to_grayscale(&self) -> Self {
+        let l = self.luminance();
+        Self { r: l, g: l, b: l }
+    }
+
+    /// Scale the brightness of each channel by a factor (clamped to 0..1)
+    ///
+    /// Values are rounded down so scaling by 0.5 matches halving a channel with a shift.
+    pub fn scaled(&self, factor: f32) -> Self {
+        let factor = factor.clamp(0.0, 1.0);
+        Self {
+            r: (self.r as f32 * factor) as u8,
+            g: (self.g as f32 * factor) as u8,
+            b: (self.b as f32 * factor) as u8,
+        }
+    }
+
+    /// Dim the colour to a percentage (0..100) of its current brightness
+    pub fn dim(&self, percent: u8) -> Self {
+        self.scaled(percent as f32 / 100.0)
+    }
+
+    /// Linearly interpolate between two colours (t is clamped to 0..1)
+    pub fn lerp(a: Colour, b: Colour, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Self {
+            r: channel(a.r, b.r),
+            g: channel(a.g, b.g),
+            b: channel(a.b, b.b),
+        }
+    }
+
     /// Return the components of this colour
     pub fn components(&self) -> (u8, u8, u8) {
         (self.r, self.g, self.b)
     }
 
+    ///
+    /// Convert colour into hue (degrees 0..360), saturation (0..1) and value (0..1)
+    ///
+    /// Greys (including black and white) have a hue and saturation of 0.
+    ///
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let r = self.r as f32 / 255.0;
+        let g = self.g as f32 / 255.0;
+        let b = self.b as f32 / 255.0;
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let h = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * (((b - r) / delta) + 2.0)
+        } else {
+            60.0 * (((r - g) / delta) + 4.0)
+        };
+        let s = if max == 0.0 { 0.0 } else { delta / max };
+
+        (h, s, max)
+    }
+
     ///
     /// Convert colour into a 24bit value
     ///
+    /// Red is stored in the low byte (0xBBGGRR), mirroring `from_u24`.
+    ///
     pub fn as_u24(&self) -> u32 {
         let c = self.r as u32 | ((self.g as u32) << 8) | ((self.b as u32) << 16);
         c
     }
+
+    ///
+    /// Convert colour into a 0xRRGGBB value
+    ///
+    pub fn to_rgb_u32(&self) -> u32 {
+        ((self.r as u32) << 16) | ((self.g as u32) << 8) | self.b as u32
+    }
+}
+
+///
+/// Indexed set of colours
+///
+/// Used to define a fixed scheme of colours (eg for LEDs) and to quantise arbitrary colours to
+/// that scheme.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Palette {
+    colours: Vec<Colour>,
+}
+
+impl Palette {
+    pub fn new(colours: Vec<Colour>) -> Self {
+        Self { colours }
+    }
+
+    /// Palette of the primary and secondary colours used by `Colour::random_indexed`
+    pub fn primary() -> Self {
+        Self::new(COLOURS.to_vec())
+    }
+
+    /// Colour at an index
+    pub fn get(&self, index: usize) -> Option<Colour> {
+        self.colours.get(index).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.colours.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colours.is_empty()
+    }
+
+    pub fn colours(&self) -> &[Colour] {
+        &self.colours
+    }
+
+    /// Add a colour to the end of the palette, returns its index
+    pub fn push(&mut self, colour: Colour) -> usize {
+        self.colours.push(colour);
+        self.colours.len() - 1
+    }
+
+    /// Index of the palette entry closest to a colour (by Euclidean distance in RGB space)
+    ///
+    /// The first of equally close entries is chosen, an empty palette returns 0.
+    pub fn nearest(&self, colour: Colour) -> usize {
+        let distance = |entry: &Colour| {
+            let channel = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            channel(entry.r, colour.r) + channel(entry.g, colour.g) + channel(entry.b, colour.b)
+        };
+
+        self.colours
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, entry)| distance(entry))
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
+
+    /// Random colour from the palette
+    pub fn random(&self) -> Option<Colour> {
+        if self.colours.is_empty() {
+            return None;
+        }
+        let mut rng = rand::thread_rng();
+        Some(self.colours[rng.gen_range(0..self.colours.len())])
+    }
+}
+
+impl From<Vec<Colour>> for Palette {
+    fn from(colours: Vec<Colour>) -> Self {
+        Self::new(colours)
+    }
+}
+
+///
+/// Colours are serialised as "#RRGGBB" strings, either a hex string or a map of `r`, `g`, `b`
+/// components can be deserialised.
+///
+#[cfg(feature = "serde")]
+impl serde::Serialize for Colour {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Colour {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Hex(String),
+            Components { r: u8, g: u8, b: u8 },
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Hex(s) => Colour::from_hex(&s).map_err(serde::de::Error::custom),
+            Repr::Components { r, g, b } => Ok(Colour::new(r, g, b)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_hsv_primaries() {
+        assert_eq!(Colour::from_hsv(0.0, 1.0, 1.0).components(), (0xFF, 0, 0));
+        assert_eq!(Colour::from_hsv(120.0, 1.0, 1.0).components(), (0, 0xFF, 0));
+        assert_eq!(Colour::from_hsv(240.0, 1.0, 1.0).components(), (0, 0, 0xFF));
+        assert_eq!(Colour::from_hsv(360.0, 1.0, 1.0).components(), (0xFF, 0, 0));
+    }
+
+    #[test]
+    fn from_hsv_grey() {
+        assert_eq!(
+            Colour::from_hsv(200.0, 0.0, 0.5).components(),
+            (0x80, 0x80, 0x80)
+        );
+        assert_eq!(
+            Colour::new(0x80, 0x80, 0x80).to_hsv(),
+            (0.0, 0.0, 128.0 / 255.0)
+        );
+    }
+
+    #[test]
+    fn hsv_round_trip() {
+        for colour in COLOURS.iter() {
+            let (h, s, v) = colour.to_hsv();
+            assert_eq!(Colour::from_hsv(h, s, v).components(), colour.components());
+        }
+
+        let colour = Colour::new(0x12, 0xAB, 0x60);
+        let (h, s, v) = colour.to_hsv();
+        assert_eq!(Colour::from_hsv(h, s, v).components(), colour.components());
+    }
+
+    #[test]
+    fn from_hex_forms() {
+        assert_eq!(
+            Colour::from_hex("#12AB60").unwrap().components(),
+            (0x12, 0xAB, 0x60)
+        );
+        assert_eq!(
+            Colour::from_hex("12ab60").unwrap().components(),
+            (0x12, 0xAB, 0x60)
+        );
+        assert_eq!(
+            Colour::from_hex("#F80").unwrap().components(),
+            (0xFF, 0x88, 0x00)
+        );
+    }
+
+    #[test]
+    fn luminance_of_primaries() {
+        assert_eq!(Colour::RED.luminance(), 76);
+        assert_eq!(Colour::GREEN.luminance(), 149);
+        assert_eq!(Colour::BLUE.luminance(), 29);
+        assert_eq!(Colour::WHITE.luminance(), 0xFF);
+        assert_eq!(Colour::BLACK.luminance(), 0x00);
+
+        assert_eq!(Colour::GREEN.to_grayscale().components(), (149, 149, 149));
+        assert_eq!(Colour::BLUE.as_1bit(), 0xFF);
+        assert_eq!(Colour::BLUE.as_1bit_luminance(), 0x00);
+        assert_eq!(Colour::WHITE.as_1bit_luminance(), 0xFF);
+    }
+
+    #[test]
+    fn palette_lookup_and_nearest() {
+        let mut palette = Palette::primary();
+        assert_eq!(palette.len(), 6);
+        assert_eq!(palette.get(1), Some(Colour::GREEN));
+        assert_eq!(palette.get(6), None);
+
+        assert_eq!(palette.nearest(Colour::new(0xE0, 0x10, 0x20)), 0);
+        assert_eq!(palette.nearest(Colour::new(0x10, 0xD0, 0xE0)), 4);
+
+        let white = palette.push(Colour::WHITE);
+        assert_eq!(palette.nearest(Colour::new(0xF0, 0xF0, 0xF0)), white);
+        assert_eq!(Palette::default().nearest(Colour::RED), 0);
+        assert!(Palette::default().random().is_none());
+    }
+
+    #[test]
+    fn from_hex_invalid() {
+        for s in ["", "#", "#12AB6", "F80", "#12AB6G", "#1234567", "#+1+2+3"] {
+            assert!(matches!(Colour::from_hex(s), Err(Error::InvalidColour)));
+        }
+    }
+
+    #[test]
+    fn scaled_half_white() {
+        assert_eq!(Colour::WHITE.scaled(0.5).components(), (0x7F, 0x7F, 0x7F));
+        assert_eq!(Colour::WHITE.scaled(2.0).components(), (0xFF, 0xFF, 0xFF));
+        assert_eq!(Colour::WHITE.scaled(-1.0).components(), (0, 0, 0));
+    }
+
+    #[test]
+    fn dim_percent() {
+        assert_eq!(Colour::WHITE.dim(50).components(), (0x7F, 0x7F, 0x7F));
+        assert_eq!(Colour::new(200, 100, 10).dim(10).components(), (20, 10, 1));
+        assert_eq!(Colour::RED.dim(100).components(), (0xFF, 0, 0));
+    }
+
+    #[test]
+    fn lerp_rounds_to_nearest() {
+        let mid = Colour::lerp(Colour::BLACK, Colour::WHITE, 0.5);
+        assert_eq!(mid, Colour::new(0x80, 0x80, 0x80));
+
+        assert_eq!(Colour::lerp(Colour::RED, Colour::BLUE, -1.0), Colour::RED);
+        assert_eq!(Colour::lerp(Colour::RED, Colour::BLUE, 2.0), Colour::BLUE);
+    }
+
+    #[test]
+    fn u24_byte_order() {
+        let colour = Colour::from_u24(0x123456);
+        assert_eq!(colour.components(), (0x56, 0x34, 0x12));
+        assert_eq!(colour.as_u24(), 0x123456);
+    }
+
+    #[test]
+    fn rgb_u32_byte_order() {
+        let colour = Colour::from_rgb_u32(0xFF123456);
+        assert_eq!(colour.components(), (0x12, 0x34, 0x56));
+        assert_eq!(colour.to_rgb_u32(), 0x123456);
+        assert_eq!(Colour::from_hex("#123456").unwrap().to_rgb_u32(), 0x123456);
+    }
+
+    #[test]
+    fn to_hex() {
+        assert_eq!(Colour::new(0x12, 0xAB, 0x00).to_hex(), "#12AB00");
+        assert_eq!(
+            Colour::from_hex(&Colour::BLUE.to_hex()).unwrap(),
+            Colour::BLUE
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let colour = Colour::new(0x12, 0xAB, 0x60);
+        let json = serde_json::to_string(&colour).unwrap();
+        assert_eq!(json, "\"#12AB60\"");
+        assert_eq!(serde_json::from_str::<Colour>(&json).unwrap(), colour);
+
+        let colour: Colour = serde_json::from_str(r#"{"r": 1, "g": 2, "b": 3}"#).unwrap();
+        assert_eq!(colour.components(), (1, 2, 3));
+        assert!(serde_json::from_str::<Colour>("\"#12AB\"").is_err());
+    }
 }
@@ -1,5 +1,27 @@
 use crate::colour::Colour;
-use crate::events::{Button, EventTask};
+use crate::devices::xy_to_pad;
+use crate::error::Error;
+use crate::events::{Button, EventContext, EventHandler, EventTask};
+use std::ops::ControlFlow;
+
+///
+/// How a controller schedules device IO across calls to `tick`
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TickPolicy {
+    ///
+    /// Each tick performs one of writing the display, writing the LEDs or reading input in
+    /// turn, so input is only read on every third tick
+    ///
+    #[default]
+    RoundRobin,
+
+    ///
+    /// Read input on every tick, the display and LEDs are written in the same tick whenever
+    /// they have been changed
+    ///
+    ReadEveryTick,
+}
 
 ///
 /// Common controller behaviours
@@ -20,4 +42,187 @@ pub trait Controller: EventTask {
     /// - pad - Pad number
     /// - colour - Colour to apply
     fn set_pad_led(&mut self, pad: u8, colour: Colour);
+
+    ///
+    /// Set the State of a Pad LED by its grid coordinate, coordinates outside of the grid are
+    /// ignored (see `xy_to_pad`)
+    ///
+    /// **Arguments**
+    /// - x - Column, 0 is the left column
+    /// - y - Row, 0 is the top row
+    /// - colour - Colour to apply
+    fn set_pad_led_xy(&mut self, x: u8, y: u8, colour: Colour) {
+        if let Some(pad) = xy_to_pad(x, y) {
+            self.set_pad_led(pad, colour);
+        }
+    }
+
+    ///
+    /// Set the State of all LEDs
+    ///
+    /// **Arguments**
+    /// - colour - Colour to apply
+    fn set_all_leds(&mut self, colour: Colour);
+
+    ///
+    /// Hold LED changes back from the device until `flush_leds` is called, so a burst of
+    /// changes is written in a single report
+    ///
+    fn begin_led_batch(&mut self) {}
+
+    ///
+    /// Write any LED changes to the device immediately rather than waiting for the next tick,
+    /// ends a batch started with `begin_led_batch`
+    ///
+    fn flush_leds(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    ///
+    /// Determine if the LED of a Button displays full colour, mono LEDs are only switched on or
+    /// off (see `Colour::as_1bit`)
+    ///
+    /// **Arguments**
+    /// - button - Button associated with a LED
+    fn is_button_led_rgb(&self, _button: Button) -> bool {
+        false
+    }
+
+    ///
+    /// Determine if the Pad LEDs display full colour
+    ///
+    fn is_pad_led_rgb(&self) -> bool {
+        false
+    }
+
+    ///
+    /// Set the State of multiple Button LEDs
+    ///
+    /// **Arguments**
+    /// - leds - Buttons associated with a LED and the colour to apply
+    fn set_leds(&mut self, leds: &[(Button, Colour)]) {
+        for (button, colour) in leds {
+            self.set_button_led(*button, *colour);
+        }
+    }
+
+    ///
+    /// Run the controller, ticking it and passing generated events to the handler
+    ///
+    /// After the events of each tick are handled `frame` is called with the controller and the
+    /// handler to perform any other work (eg rendering the display), the loop continues until
+    /// it returns `ControlFlow::Break`.
+    ///
+    /// **Arguments**
+    /// - handler - Handler offered each event
+    /// - frame - Per iteration work, also decides when to stop
+    fn run<H, F>(&mut self, handler: &mut H, mut frame: F) -> Result<(), Error>
+    where
+        Self: Sized,
+        H: EventHandler,
+        F: FnMut(&mut Self, &mut H) -> ControlFlow<()>,
+    {
+        let mut context = EventContext::new();
+        loop {
+            self.tick(&mut context)?;
+            for event in context.drain() {
+                handler.handle(&event);
+            }
+            if frame(self, handler).is_break() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<C: Controller + ?Sized> Controller for Box<C> {
+    fn set_button_led(&mut self, button: Button, colour: Colour) {
+        (**self).set_button_led(button, colour);
+    }
+
+    fn set_pad_led(&mut self, pad: u8, colour: Colour) {
+        (**self).set_pad_led(pad, colour);
+    }
+
+    fn set_all_leds(&mut self, colour: Colour) {
+        (**self).set_all_leds(colour);
+    }
+
+    fn set_leds(&mut self, leds: &[(Button, Colour)]) {
+        (**self).set_leds(leds);
+    }
+
+    fn begin_led_batch(&mut self) {
+        (**self).begin_led_batch();
+    }
+
+    fn flush_leds(&mut self) -> Result<(), Error> {
+        (**self).flush_leds()
+    }
+
+    fn is_button_led_rgb(&self, button: Button) -> bool {
+        (**self).is_button_led_rgb(button)
+    }
+
+    fn is_pad_led_rgb(&self) -> bool {
+        (**self).is_pad_led_rgb()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Event;
+
+    struct CountingController {
+        ticks: usize,
+    }
+
+    impl EventTask for CountingController {
+        fn tick(&mut self, context: &mut EventContext) -> Result<(), Error> {
+            self.ticks += 1;
+            context.add_event(Event::Pad(0, 100, false));
+            Ok(())
+        }
+    }
+
+    impl Controller for CountingController {
+        fn set_button_led(&mut self, _button: Button, _colour: Colour) {}
+
+        fn set_pad_led(&mut self, _pad: u8, _colour: Colour) {}
+
+        fn set_all_leds(&mut self, _colour: Colour) {}
+    }
+
+    struct CountingHandler {
+        events: usize,
+    }
+
+    impl EventHandler for CountingHandler {
+        fn handle(&mut self, _event: &Event) -> bool {
+            self.events += 1;
+            true
+        }
+    }
+
+    #[test]
+    fn run_until_break() {
+        let mut controller: Box<dyn Controller> = Box::new(CountingController { ticks: 0 });
+        let mut handler = CountingHandler { events: 0 };
+        let mut frames = 0;
+
+        controller
+            .run(&mut handler, |_, handler| {
+                frames += 1;
+                if handler.events >= 3 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            })
+            .unwrap();
+
+        assert_eq!(handler.events, 3);
+        assert_eq!(frames, 3);
+    }
 }
@@ -0,0 +1,488 @@
+use hidapi::HidDevice;
+
+use crate::colour::Colour;
+use crate::controller::{Controller, TickPolicy};
+use crate::display::MonochromeCanvas;
+use crate::error::Error;
+use crate::events::{Button, Event, EventContext, EventTask};
+
+use super::{
+    encoder_direction, is_button_pressed, normalize_pad_pressure, pad_report_values,
+    read_device_info, DeviceCore, DeviceInfo, Report, TickTask, INPUT_BUFFER_SIZE,
+};
+
+// LEDs, all LEDs on the Mk1 are single colour
+// Experimental: LED indices have not been verified against hardware
+pub const LED_F1: u8 = 0x00;
+pub const LED_F2: u8 = 0x01;
+pub const LED_F3: u8 = 0x02;
+pub const LED_CONTROL: u8 = 0x03;
+pub const LED_NAV: u8 = 0x04;
+pub const LED_BROWSE_LEFT: u8 = 0x05;
+pub const LED_BROWSE_RIGHT: u8 = 0x06;
+pub const LED_MAIN: u8 = 0x07;
+pub const LED_GROUP: u8 = 0x08;
+pub const LED_BROWSE: u8 = 0x09;
+pub const LED_SAMPLING: u8 = 0x0A;
+pub const LED_NOTE_REPEAT: u8 = 0x0B;
+pub const LED_RESTART: u8 = 0x0C;
+pub const LED_TRANSPORT_LEFT: u8 = 0x0D;
+pub const LED_TRANSPORT_RIGHT: u8 = 0x0E;
+pub const LED_GRID: u8 = 0x0F;
+pub const LED_PLAY: u8 = 0x10;
+pub const LED_REC: u8 = 0x11;
+pub const LED_ERASE: u8 = 0x12;
+pub const LED_SHIFT: u8 = 0x13;
+pub const LED_SCENE: u8 = 0x14;
+pub const LED_PATTERN: u8 = 0x15;
+pub const LED_PADMODE: u8 = 0x16;
+pub const LED_VIEW: u8 = 0x17;
+pub const LED_DUPLICATE: u8 = 0x18;
+pub const LED_SELECT: u8 = 0x19;
+pub const LED_SOLO: u8 = 0x1A;
+pub const LED_MUTE: u8 = 0x1B;
+pub const LED_PAD13: u8 = 0x1C;
+pub const LED_PAD14: u8 = 0x1D;
+pub const LED_PAD15: u8 = 0x1E;
+pub const LED_PAD16: u8 = 0x1F;
+pub const LED_PAD09: u8 = 0x20;
+pub const LED_PAD10: u8 = 0x21;
+pub const LED_PAD11: u8 = 0x22;
+pub const LED_PAD12: u8 = 0x23;
+pub const LED_PAD05: u8 = 0x24;
+pub const LED_PAD06: u8 = 0x25;
+pub const LED_PAD07: u8 = 0x26;
+pub const LED_PAD08: u8 = 0x27;
+pub const LED_PAD01: u8 = 0x28;
+pub const LED_PAD02: u8 = 0x29;
+pub const LED_PAD03: u8 = 0x2A;
+pub const LED_PAD04: u8 = 0x2B;
+
+// Buttons
+// Experimental: button bit positions have not been verified against hardware
+pub const BUTTON_RESTART: u8 = 0x00;
+pub const BUTTON_TRANSPORT_LEFT: u8 = 0x01;
+pub const BUTTON_TRANSPORT_RIGHT: u8 = 0x02;
+pub const BUTTON_GRID: u8 = 0x03;
+pub const BUTTON_PLAY: u8 = 0x04;
+pub const BUTTON_REC: u8 = 0x05;
+pub const BUTTON_ERASE: u8 = 0x06;
+pub const BUTTON_SHIFT: u8 = 0x07;
+pub const BUTTON_GROUP: u8 = 0x08;
+pub const BUTTON_BROWSE: u8 = 0x09;
+pub const BUTTON_SAMPLING: u8 = 0x0A;
+pub const BUTTON_NOTE_REPEAT: u8 = 0x0B;
+pub const BUTTON_MAIN_ENCODER: u8 = 0x0C;
+pub const BUTTON_F1: u8 = 0x10;
+pub const BUTTON_F2: u8 = 0x11;
+pub const BUTTON_F3: u8 = 0x12;
+pub const BUTTON_CONTROL: u8 = 0x13;
+pub const BUTTON_NAV: u8 = 0x14;
+pub const BUTTON_BROWSE_LEFT: u8 = 0x15;
+pub const BUTTON_BROWSE_RIGHT: u8 = 0x16;
+pub const BUTTON_MAIN: u8 = 0x17;
+pub const BUTTON_SCENE: u8 = 0x18;
+pub const BUTTON_PATTERN: u8 = 0x19;
+pub const BUTTON_PAD_MODE: u8 = 0x1A;
+pub const BUTTON_VIEW: u8 = 0x1B;
+pub const BUTTON_DUPLICATE: u8 = 0x1C;
+pub const BUTTON_SELECT: u8 = 0x1D;
+pub const BUTTON_SOLO: u8 = 0x1E;
+pub const BUTTON_MUTE: u8 = 0x1F;
+pub const BUTTON_NONE: u8 = 0x20;
+
+const LED_COUNT: usize = 44;
+const BUTTON_COUNT: usize = 32;
+const PAD_COUNT: usize = 16;
+// Pressure (12bit) a pad must exceed to be considered pressed
+const PAD_THRESHOLD: u16 = 512;
+
+const LED_ADDR: u8 = 0x80;
+
+///
+/// Maschine Mikro Mk1 Controller
+///
+/// Shares the display and report framing of the Mk2 but has single colour LEDs and a
+/// different button matrix.
+///
+/// Support is experimental, the button and LED tables have not been verified against
+/// hardware so some buttons may be reported as the wrong [`Button`] or light the wrong LED.
+///
+/// Requires a valid HID device
+///
+pub struct MaschineMikroMk1 {
+    pub device: HidDevice,
+    pub display: MonochromeCanvas,
+    core: DeviceCore,
+    leds: [u8; LED_COUNT],
+    leds_dirty: bool,
+    leds_batched: bool,
+    button_states: [bool; BUTTON_COUNT],
+    shift_pressed: bool,
+    pads_data: [u16; PAD_COUNT],
+    pads_status: [bool; PAD_COUNT],
+    encoder_value: u8,
+}
+
+impl MaschineMikroMk1 {
+    pub const VENDOR_ID: u16 = 0x17cc;
+    pub const PRODUCT_ID: u16 = 0x1110;
+
+    /// Determine if an LED is RGB or Mono, all LEDs of the Mk1 are Mono
+    pub fn led_is_rgb(_led: u8) -> bool {
+        false
+    }
+
+    pub fn new(device: HidDevice) -> Self {
+        MaschineMikroMk1 {
+            device,
+            display: MonochromeCanvas::new(128, 64),
+            core: DeviceCore::new(),
+            leds: [0; LED_COUNT],
+            leds_dirty: true,
+            leds_batched: false,
+            button_states: [false; BUTTON_COUNT],
+            shift_pressed: false,
+            pads_data: [0; PAD_COUNT],
+            pads_status: [false; PAD_COUNT],
+            encoder_value: 0,
+        }
+    }
+
+    /// Last pressure value (12bit) read from a pad
+    pub fn pad_value(&self, pad: u8) -> u16 {
+        self.pads_data.get(pad as usize).copied().unwrap_or(0)
+    }
+
+    /// Last pressure read from a pad scaled to 0.0-1.0, pressure up to the pad threshold is 0.0
+    /// so the full range is available once a pad is pressed
+    pub fn pad_pressure_normalized(&self, pad: u8) -> f32 {
+        normalize_pad_pressure(self.pad_value(pad), PAD_THRESHOLD)
+    }
+
+    /// Set how device IO is scheduled across calls to `tick` (defaults to round robin)
+    pub fn set_tick_policy(&mut self, policy: TickPolicy) {
+        self.core.set_tick_policy(policy);
+    }
+
+    /// Set the timeout in milliseconds when reading reports from the device, see
+    /// `MaschineMikroMk2::set_read_timeout`
+    pub fn set_read_timeout(&mut self, ms: i32) -> Result<(), Error> {
+        self.core.set_read_timeout(&self.device, ms)
+    }
+
+    /// Limit how often the display is written to a target frame rate (0, the default, is
+    /// unlimited), see `MaschineMikroMk2::set_target_fps`
+    pub fn set_target_fps(&mut self, fps: u32) {
+        self.core.set_target_fps(fps);
+    }
+
+    /// Set the maximum number of reports drained from the device each time input is read
+    /// (defaults to 32), see `MaschineMikroMk2::set_reports_per_read`
+    pub fn set_reports_per_read(&mut self, reports: usize) {
+        self.core.set_reports_per_read(reports);
+    }
+
+    /// Set how often pad reports are processed while draining reports (defaults to every 7th
+    /// report read), see `MaschineMikroMk2::set_pad_report_interval`
+    pub fn set_pad_report_interval(&mut self, interval: usize) {
+        self.core.set_pad_report_interval(interval);
+    }
+
+    /// Turn the graphics display on or off, see `MaschineMikroMk2::set_display_on`
+    pub fn set_display_on(&mut self, on: bool) -> Result<(), Error> {
+        self.core
+            .set_display_on(&self.device, &mut self.display, on)
+    }
+
+    /// Determine if the graphics display is on
+    pub fn is_display_on(&self) -> bool {
+        self.core.is_display_on()
+    }
+
+    /// Set a callback passed the raw bytes of any report that is not recognised, see
+    /// `MaschineMikroMk2::set_raw_report_hook`
+    pub fn set_raw_report_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut(&[u8]) + Send + 'static,
+    {
+        self.core.set_raw_report_hook(Some(Box::new(hook)));
+    }
+
+    /// Remove a callback set by `set_raw_report_hook`
+    pub fn clear_raw_report_hook(&mut self) {
+        self.core.set_raw_report_hook(None);
+    }
+
+    /// Read the product string and serial number of the device, eg to tell multiple
+    /// controllers apart
+    pub fn device_info(&self) -> Result<DeviceInfo, Error> {
+        read_device_info(&self.device, Self::VENDOR_ID, Self::PRODUCT_ID)
+    }
+
+    /// Write a complete frame of display data directly to the device, see
+    /// `MaschineMikroMk2::write_display_raw`
+    pub fn write_display_raw(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.core.write_display_raw(&self.device, data)
+    }
+
+    /// Clear the display and turn off all LEDs
+    ///
+    /// Called when the controller is dropped (ignoring any errors) so the device is left blank
+    /// when an application exits.
+    pub fn shutdown(&mut self) -> Result<(), Error> {
+        self.core.clear_display(&self.device, &mut self.display)?;
+
+        self.leds = [0; LED_COUNT];
+        self.leds_dirty = true;
+        self.leds_batched = false;
+        self.send_leds()
+    }
+
+    /// Write the LED state to the device if it has been updated, unless a batch is in progress
+    fn send_leds(&mut self) -> Result<(), Error> {
+        if self.leds_batched {
+            return Ok(());
+        }
+        if self.leds_dirty {
+            let mut buffer: Vec<u8> = vec![LED_ADDR];
+            buffer.extend_from_slice(&self.leds);
+            self.device
+                .write(buffer.as_slice())
+                .map_err(Error::from_device_io)?;
+        }
+        self.leds_dirty = false;
+
+        Ok(())
+    }
+
+    /// Read incoming reports from the device
+    fn read(&mut self, context: &mut EventContext) -> Result<(), Error> {
+        let mut buffer = [0u8; INPUT_BUFFER_SIZE];
+
+        for idx in 0..self.core.reports_per_read() {
+            match self.core.read_report(&self.device, &mut buffer, idx)? {
+                Report::End => break,
+                Report::Handled => {}
+                Report::Buttons(report) => self.process_buttons(report, context)?,
+                Report::Pads(report) => self.process_pads(report, context),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Process a buttons report message
+    fn process_buttons(&mut self, buffer: &[u8], context: &mut EventContext) -> Result<(), Error> {
+        if buffer.len() < 5 {
+            return Err(Error::InvalidReport);
+        }
+
+        // Scan buttons
+        for btn in BUTTON_RESTART..BUTTON_NONE {
+            let button_pressed = is_button_pressed(buffer, btn);
+            if button_pressed != self.button_states[btn as usize] {
+                self.button_states[btn as usize] = button_pressed;
+
+                if btn == BUTTON_SHIFT {
+                    self.shift_pressed = button_pressed;
+                    self.set_led(
+                        LED_SHIFT,
+                        if button_pressed {
+                            Colour::WHITE
+                        } else {
+                            Colour::BLACK
+                        },
+                    );
+                } else {
+                    let button = self.as_device_button(btn);
+                    if !matches!(button, Button::Unknown) {
+                        context.add_event(Event::Button(
+                            button,
+                            button_pressed,
+                            self.shift_pressed,
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Handle encoder data
+        let encoder_value = buffer[4];
+        if self.encoder_value != encoder_value {
+            let direction = encoder_direction(self.encoder_value, encoder_value);
+            self.encoder_value = encoder_value;
+            context.add_event(Event::Encoder(0, direction, 1, self.shift_pressed));
+        }
+
+        Ok(())
+    }
+
+    /// Process a pads report message
+    fn process_pads(&mut self, buffer: &[u8], context: &mut EventContext) {
+        for (pad, value) in pad_report_values(buffer) {
+            let pressed = value > PAD_THRESHOLD;
+
+            self.pads_data[pad] = value;
+            if pressed && !self.pads_status[pad] {
+                self.pads_status[pad] = true;
+                context.add_event(Event::Pad(
+                    pad as u8,
+                    (value >> 4) as u8,
+                    self.shift_pressed,
+                ));
+            } else if !pressed && self.pads_status[pad] {
+                self.pads_status[pad] = false;
+                context.add_event(Event::PadRelease(pad as u8, self.shift_pressed));
+            }
+        }
+    }
+
+    /// Set the state of an LED, any colour is rendered as on or off
+    fn set_led(&mut self, led: u8, colour: Colour) {
+        let m = colour.as_1bit();
+        self.leds_dirty |= m != self.leds[led as usize];
+        self.leds[led as usize] = m;
+    }
+
+    /// Convert a button code into a button enum
+    fn as_device_button(&self, button: u8) -> Button {
+        match button {
+            BUTTON_ERASE => Button::Erase,
+            BUTTON_REC => Button::Rec,
+            BUTTON_PLAY => Button::Play,
+            BUTTON_GRID => Button::Grid,
+            BUTTON_TRANSPORT_RIGHT => Button::TransportRight,
+            BUTTON_TRANSPORT_LEFT => Button::TransportLeft,
+            BUTTON_RESTART => Button::Restart,
+            BUTTON_MAIN_ENCODER => Button::MainEncoder,
+            BUTTON_NOTE_REPEAT => Button::NoteRepeat,
+            BUTTON_SAMPLING => Button::Sampling,
+            BUTTON_BROWSE => Button::Browse,
+            BUTTON_GROUP => Button::Group,
+            BUTTON_MAIN => Button::Main,
+            BUTTON_BROWSE_RIGHT => Button::BrowseRight,
+            BUTTON_BROWSE_LEFT => Button::BrowseLeft,
+            BUTTON_NAV => Button::Nav,
+            BUTTON_CONTROL => Button::Control,
+            BUTTON_F3 => Button::F3,
+            BUTTON_F2 => Button::F2,
+            BUTTON_F1 => Button::F1,
+            BUTTON_MUTE => Button::Mute,
+            BUTTON_SOLO => Button::Solo,
+            BUTTON_SELECT => Button::Select,
+            BUTTON_DUPLICATE => Button::Duplicate,
+            BUTTON_VIEW => Button::View,
+            BUTTON_PAD_MODE => Button::PadMode,
+            BUTTON_PATTERN => Button::Pattern,
+            BUTTON_SCENE => Button::Scene,
+            _ => Button::Unknown,
+        }
+    }
+
+    /// Convert a button into a LED index
+    fn button_to_led(&self, button: Button) -> Option<u8> {
+        match button {
+            Button::Erase => Some(LED_ERASE),
+            Button::Rec => Some(LED_REC),
+            Button::Play => Some(LED_PLAY),
+            Button::Grid => Some(LED_GRID),
+            Button::TransportRight => Some(LED_TRANSPORT_RIGHT),
+            Button::TransportLeft => Some(LED_TRANSPORT_LEFT),
+            Button::Restart => Some(LED_RESTART),
+            Button::NoteRepeat => Some(LED_NOTE_REPEAT),
+            Button::Sampling => Some(LED_SAMPLING),
+            Button::Browse => Some(LED_BROWSE),
+            Button::Group => Some(LED_GROUP),
+            Button::Main => Some(LED_MAIN),
+            Button::BrowseRight => Some(LED_BROWSE_RIGHT),
+            Button::BrowseLeft => Some(LED_BROWSE_LEFT),
+            Button::Nav => Some(LED_NAV),
+            Button::Control => Some(LED_CONTROL),
+            Button::F3 => Some(LED_F3),
+            Button::F2 => Some(LED_F2),
+            Button::F1 => Some(LED_F1),
+            Button::Mute => Some(LED_MUTE),
+            Button::Solo => Some(LED_SOLO),
+            Button::Select => Some(LED_SELECT),
+            Button::Duplicate => Some(LED_DUPLICATE),
+            Button::View => Some(LED_VIEW),
+            Button::PadMode => Some(LED_PADMODE),
+            Button::Pattern => Some(LED_PATTERN),
+            Button::Scene => Some(LED_SCENE),
+            _ => None,
+        }
+    }
+
+    /// Convert a pad number into a LED index
+    fn pad_to_led(&self, pad: u8) -> Option<u8> {
+        match pad {
+            0x0 => Some(LED_PAD13),
+            0x1 => Some(LED_PAD14),
+            0x2 => Some(LED_PAD15),
+            0x3 => Some(LED_PAD16),
+            0x4 => Some(LED_PAD09),
+            0x5 => Some(LED_PAD10),
+            0x6 => Some(LED_PAD11),
+            0x7 => Some(LED_PAD12),
+            0x8 => Some(LED_PAD05),
+            0x9 => Some(LED_PAD06),
+            0xA => Some(LED_PAD07),
+            0xB => Some(LED_PAD08),
+            0xC => Some(LED_PAD01),
+            0xD => Some(LED_PAD02),
+            0xE => Some(LED_PAD03),
+            0xF => Some(LED_PAD04),
+            _ => None,
+        }
+    }
+}
+
+impl Controller for MaschineMikroMk1 {
+    fn set_button_led(&mut self, button: Button, colour: Colour) {
+        if let Some(led) = self.button_to_led(button) {
+            self.set_led(led, colour);
+        }
+    }
+
+    fn set_pad_led(&mut self, pad: u8, colour: Colour) {
+        if let Some(led) = self.pad_to_led(pad) {
+            self.set_led(led, colour);
+        }
+    }
+
+    fn set_all_leds(&mut self, colour: Colour) {
+        for led in 0..LED_COUNT {
+            self.set_led(led as u8, colour);
+        }
+    }
+
+    fn begin_led_batch(&mut self) {
+        self.leds_batched = true;
+    }
+
+    fn flush_leds(&mut self) -> Result<(), Error> {
+        self.leds_batched = false;
+        self.send_leds()
+    }
+}
+
+impl EventTask for MaschineMikroMk1 {
+    fn tick(&mut self, context: &mut EventContext) -> Result<(), Error> {
+        for task in self.core.next_tick() {
+            match task {
+                TickTask::Frame => self.core.send_frame(&self.device, &mut self.display)?,
+                TickTask::Leds => self.send_leds()?,
+                TickTask::Read => self.read(context)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for MaschineMikroMk1 {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
@@ -1,12 +1,16 @@
 use hidapi::HidDevice;
+use std::time::{Duration, Instant};
 
 use crate::colour::Colour;
-use crate::controller::Controller;
-use crate::display::{Canvas, MonochromeCanvas};
+use crate::controller::{Controller, TickPolicy};
+use crate::display::MonochromeCanvas;
 use crate::error::Error;
-use crate::events::{Button, Direction, Event, EventContext, EventTask};
+use crate::events::{Button, Event, EventContext, EventTask};
 
-const INPUT_BUFFER_SIZE: usize = 512;
+use super::{
+    encoder_direction, is_button_pressed, normalize_pad_pressure, pad_report_values,
+    read_device_info, DeviceCore, DeviceInfo, Report, TickTask, INPUT_BUFFER_SIZE,
+};
 
 // LEDs
 pub const LED_F1: u8 = 0x00;
@@ -91,8 +95,25 @@ const LED_COUNT: usize = 78;
 const BUTTON_COUNT: usize = 45;
 const PAD_COUNT: usize = 16;
 
-const DISPLAY_ADDR: u8 = 0xE0;
 const LED_ADDR: u8 = 0x80;
+// Display brightness report, the layout has not been confirmed against hardware
+const DISPLAY_BRIGHTNESS_ADDR: u8 = 0xE1;
+
+const DEFAULT_GAMMA: f32 = 2.2;
+const DEFAULT_LONG_PRESS: Duration = Duration::from_millis(500);
+const DEFAULT_DOUBLE_TAP: Duration = Duration::from_millis(250);
+const DEFAULT_AFTERTOUCH_DELTA: u16 = 32;
+const DEFAULT_CHORD_SIZE: usize = 2;
+const DEFAULT_PAD_THRESHOLD: u16 = 512;
+const DEFAULT_PAD_HYSTERESIS: u16 = 0;
+
+// Encoder acceleration curve, a detent that follows the previous one within the interval is
+// reported as the given number of steps. Slower turns are reported as a single step.
+const ENCODER_ACCELERATION: [(Duration, u8); 3] = [
+    (Duration::from_millis(30), 8),
+    (Duration::from_millis(60), 4),
+    (Duration::from_millis(120), 2),
+];
 
 ///
 /// Maschine Mikro Mk2 Controller
@@ -101,69 +122,371 @@ const LED_ADDR: u8 = 0x80;
 ///
 pub struct MaschineMikroMk2 {
     pub device: HidDevice,
-    tick_state: u8,
     pub display: MonochromeCanvas,
+    core: DeviceCore,
+    display_brightness: u8,
+    display_brightness_dirty: bool,
+    display_brightness_report: bool,
     leds: [u8; LED_COUNT],
     leds_dirty: bool,
+    leds_batched: bool,
+    led_brightness: f32,
+    gamma_table: [u8; 256],
     button_states: [bool; BUTTON_COUNT],
+    button_pressed_at: [Option<Instant>; BUTTON_COUNT],
+    long_press_threshold: Duration,
     shift_pressed: bool,
     pads_data: [u16; PAD_COUNT],
     pads_status: [bool; PAD_COUNT],
+    pad_threshold: u16,
+    pad_hysteresis: u16,
+    pads_tapped_at: [Option<Instant>; PAD_COUNT],
+    double_tap_interval: Duration,
+    pads_aftertouch: [u16; PAD_COUNT],
+    aftertouch: bool,
+    aftertouch_delta: u16,
+    chord: Vec<(u8, u8)>,
+    chord_started_at: Option<Instant>,
+    chord_window: Option<Duration>,
+    chord_size: usize,
     encoder_value: u8,
+    encoder_turned_at: Option<Instant>,
+    encoder_acceleration: bool,
+    pad_hit_feedback: Option<Colour>,
 }
 
 impl MaschineMikroMk2 {
     pub const VENDOR_ID: u16 = 0x17cc;
     pub const PRODUCT_ID: u16 = 0x1200;
 
+    /// Determine if an LED is RGB or Mono, the Group button and the pads are RGB (3 consecutive
+    /// LED values) all other LEDs are Mono
+    pub fn led_is_rgb(led: u8) -> bool {
+        (led == LED_GROUP) | (LED_PAD13..=LED_PAD04).contains(&led)
+    }
+
+    /// Start configuring a controller, see `MaschineMikroMk2Builder`
+    pub fn builder() -> MaschineMikroMk2Builder {
+        MaschineMikroMk2Builder::default()
+    }
+
     pub fn new(device: HidDevice) -> Self {
         MaschineMikroMk2 {
             device,
-            tick_state: 0,
             display: MonochromeCanvas::new(128, 64),
+            core: DeviceCore::new(),
+            display_brightness: 0xFF,
+            display_brightness_dirty: false,
+            display_brightness_report: false,
             leds: [0; LED_COUNT],
             leds_dirty: true,
+            leds_batched: false,
+            led_brightness: 1.0,
+            gamma_table: gamma_table(DEFAULT_GAMMA),
             button_states: [false; BUTTON_COUNT],
+            button_pressed_at: [None; BUTTON_COUNT],
+            long_press_threshold: DEFAULT_LONG_PRESS,
             shift_pressed: false,
             pads_data: [0; PAD_COUNT],
             pads_status: [false; PAD_COUNT],
+            pad_threshold: DEFAULT_PAD_THRESHOLD,
+            pad_hysteresis: DEFAULT_PAD_HYSTERESIS,
+            pads_tapped_at: [None; PAD_COUNT],
+            double_tap_interval: DEFAULT_DOUBLE_TAP,
+            pads_aftertouch: [0; PAD_COUNT],
+            aftertouch: false,
+            aftertouch_delta: DEFAULT_AFTERTOUCH_DELTA,
+            chord: Vec::new(),
+            chord_started_at: None,
+            chord_window: None,
+            chord_size: DEFAULT_CHORD_SIZE,
             encoder_value: 0,
+            encoder_turned_at: None,
+            encoder_acceleration: true,
+            pad_hit_feedback: None,
         }
     }
 
-    /// Send a display frame for the graphics panel
-    fn send_frame(&mut self) -> Result<(), Error> {
-        if self.display.is_dirty() {
-            for row in (0..8).step_by(2) {
-                // The number of referenced bytes must be <= 256
-                // Eg Column width * number of rows
-                let mut buffer: Vec<u8> = vec![
-                    DISPLAY_ADDR,
-                    0x00,      // Column offset
-                    0x00,      // ?
-                    row as u8, // Row (a row is 8 pixels high)
-                    0x00,      // ?
-                    0x80,      // Columns per row, 128 is full width
-                    0x00,      // ?
-                    0x02,      // Number of rows
-                    0x00,      // ?
-                ];
-                let x_offset = row * 128;
-                buffer.extend_from_slice(&self.display.data()[x_offset..(x_offset + 256)]);
-                self.device.write(buffer.as_slice())?;
-            }
+    /// Determine if a button is currently held
+    pub fn is_button_pressed(&self, button: Button) -> bool {
+        match self.as_button_code(button) {
+            Some(btn) => self.button_states[btn as usize],
+            None => false,
+        }
+    }
+
+    /// Buttons currently held, shift is reported by `is_shift_pressed`
+    pub fn pressed_buttons(&self) -> Vec<Button> {
+        (0..BUTTON_NONE)
+            .filter(|btn| self.button_states[*btn as usize])
+            .map(|btn| self.as_device_button(btn))
+            .filter(|button| *button != Button::Unknown)
+            .collect()
+    }
+
+    /// Determine if a pad is currently held
+    pub fn is_pad_pressed(&self, pad: u8) -> bool {
+        self.pads_status.get(pad as usize).copied().unwrap_or(false)
+    }
+
+    /// Pad numbers of the pads currently held in ascending order
+    pub fn pressed_pads(&self) -> Vec<u8> {
+        (0..PAD_COUNT as u8)
+            .filter(|pad| self.pads_status[*pad as usize])
+            .collect()
+    }
+
+    /// Determine if the shift button is currently held
+    pub fn is_shift_pressed(&self) -> bool {
+        self.shift_pressed
+    }
+
+    /// Absolute position (0x00-0x0F) of the main encoder
+    ///
+    /// The position wraps around, a single detent moves it between 0x0F and 0x00 in either
+    /// direction so it should not be compared directly to work out the direction of a turn.
+    pub fn encoder_value(&self) -> u8 {
+        self.encoder_value
+    }
+
+    /// Last pressure value (12bit) read from a pad
+    pub fn pad_value(&self, pad: u8) -> u16 {
+        self.pads_data.get(pad as usize).copied().unwrap_or(0)
+    }
+
+    /// Last pressure read from a pad scaled to 0.0-1.0, pressure up to the pad threshold is 0.0
+    /// so the full range is available once a pad is pressed
+    pub fn pad_pressure_normalized(&self, pad: u8) -> f32 {
+        normalize_pad_pressure(self.pad_value(pad), self.pad_threshold)
+    }
+
+    /// Set the pressure (12bit) a pad must exceed to be considered pressed (defaults to 512)
+    pub fn set_pad_threshold(&mut self, threshold: u16) {
+        self.pad_threshold = threshold;
+    }
+
+    /// Set how far below the threshold a pressed pad must drop before it is considered
+    /// released (defaults to 0), this prevents jitter around the threshold
+    pub fn set_pad_hysteresis(&mut self, margin: u16) {
+        self.pad_hysteresis = margin;
+    }
+
+    /// Set the brightness (0.0-1.0) applied to all LEDs as they are sent to the device
+    ///
+    /// The colours held for each LED are not changed, only the physical output is dimmed.
+    pub fn set_led_brightness(&mut self, factor: f32) {
+        self.led_brightness = factor.clamp(0.0, 1.0);
+        self.leds_dirty = true;
+    }
+
+    /// Set how device IO is scheduled across calls to `tick` (defaults to round robin)
+    pub fn set_tick_policy(&mut self, policy: TickPolicy) {
+        self.core.set_tick_policy(policy);
+    }
+
+    /// Set the timeout in milliseconds when reading reports from the device
+    ///
+    /// By default reads block until a report is available, a timeout of 0 returns immediately
+    /// and -1 restores blocking reads. With the default tick policy reports are only read on
+    /// every third call to `tick` (frame, LEDs then input), with a timeout a quiet device delays
+    /// that tick by at most the timeout and the remaining ticks are not affected.
+    pub fn set_read_timeout(&mut self, ms: i32) -> Result<(), Error> {
+        self.core.set_read_timeout(&self.device, ms)
+    }
+
+    /// Limit how often the display is written to a target frame rate (0, the default, is
+    /// unlimited)
+    ///
+    /// When limited a frame tick before the next frame is due skips the write and keeps the
+    /// display dirty so it is sent on a later tick, `tick` never sleeps and input is read on
+    /// every input tick (see `set_tick_policy`). To reduce CPU usage of the tick loop set a read
+    /// timeout (see `set_read_timeout`), reading then waits for input rather than spinning and
+    /// returns as soon as a report arrives.
+    pub fn set_target_fps(&mut self, fps: u32) {
+        self.core.set_target_fps(fps);
+    }
+
+    /// Set the maximum number of reports drained from the device each time input is read
+    /// (defaults to 32)
+    ///
+    /// Reading stops early when no report is available within the read timeout.
+    pub fn set_reports_per_read(&mut self, reports: usize) {
+        self.core.set_reports_per_read(reports);
+    }
+
+    /// Set how often pad reports are processed while draining reports (defaults to every 7th
+    /// report read, 1 processes every pad report)
+    ///
+    /// Each pad report holds the state of every pad so skipping reports only delays changes,
+    /// though a very short strike can be missed.
+    pub fn set_pad_report_interval(&mut self, interval: usize) {
+        self.core.set_pad_report_interval(interval);
+    }
+
+    /// Set the gamma correction applied to RGB LEDs (defaults to 2.2, 1.0 disables correction)
+    pub fn set_gamma(&mut self, gamma: f32) {
+        self.gamma_table = gamma_table(gamma);
+    }
+
+    /// Set how long a button must be held before a long press is reported (defaults to 500ms)
+    pub fn set_long_press_threshold(&mut self, threshold: Duration) {
+        self.long_press_threshold = threshold;
+    }
+
+    /// Set the window in which a second strike of a pad is reported as a double tap
+    /// (defaults to 250ms)
+    pub fn set_double_tap_interval(&mut self, interval: Duration) {
+        self.double_tap_interval = interval;
+    }
+
+    /// Enable or disable pad aftertouch events (disabled by default)
+    ///
+    /// When enabled held pads report their pressure whenever it changes by more than the
+    /// aftertouch delta.
+    pub fn set_aftertouch(&mut self, enabled: bool) {
+        self.aftertouch = enabled;
+    }
+
+    /// Set how far (of the 12bit pressure range) a held pad must move before aftertouch is
+    /// reported (defaults to 32)
+    pub fn set_aftertouch_delta(&mut self, delta: u16) {
+        self.aftertouch_delta = delta;
+    }
+
+    /// Enable chord detection with the window in which pad strikes are grouped, or disable
+    /// it with `None` (disabled by default)
+    ///
+    /// While enabled pad strikes are held back until the window closes, if enough pads were
+    /// struck a single chord event is reported, otherwise the strikes are reported as normal.
+    pub fn set_chord_window(&mut self, window: Option<Duration>) {
+        self.chord_window = window;
+    }
+
+    /// Set the minimum number of pads that make up a chord (defaults to 2)
+    pub fn set_chord_size(&mut self, size: usize) {
+        self.chord_size = size;
+    }
+
+    /// Enable or disable encoder acceleration (enabled by default)
+    ///
+    /// When enabled quickly turning the encoder reports multiple steps per detent, see
+    /// `ENCODER_ACCELERATION` for the curve. When disabled every detent is a single step.
+    pub fn set_encoder_acceleration(&mut self, enabled: bool) {
+        self.encoder_acceleration = enabled;
+    }
+
+    /// Light pads as they are struck (disabled by default)
+    ///
+    /// A struck pad is lit in the colour scaled by the strike velocity and turned off when it
+    /// is released, any colour set for the pad by the application is replaced.
+    pub fn set_pad_hit_feedback(&mut self, colour: Option<Colour>) {
+        self.pad_hit_feedback = colour;
+    }
+
+    /// Set the brightness of the graphics display, the setting is sent on the next frame
+    ///
+    /// The brightness is only sent once enabled with `set_display_brightness_report`.
+    pub fn set_display_brightness(&mut self, level: u8) {
+        self.display_brightness_dirty |= level != self.display_brightness;
+        self.display_brightness = level;
+    }
+
+    /// Send display brightness changes to the device (disabled by default)
+    ///
+    /// The brightness report has not been confirmed against hardware, enable it only on
+    /// devices known to accept it.
+    pub fn set_display_brightness_report(&mut self, enabled: bool) {
+        self.display_brightness_report = enabled;
+    }
+
+    /// Turn the graphics display on or off
+    ///
+    /// While off the panel is blanked and frames are not sent, pad and button input is not
+    /// affected. Turning the display back on sends the current frame.
+    pub fn set_display_on(&mut self, on: bool) -> Result<(), Error> {
+        self.core
+            .set_display_on(&self.device, &mut self.display, on)
+    }
+
+    /// Set a callback passed the raw bytes (including the report ID) of any report read from
+    /// the device that is not recognised, useful for investigating unsupported controls
+    pub fn set_raw_report_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut(&[u8]) + Send + 'static,
+    {
+        self.core.set_raw_report_hook(Some(Box::new(hook)));
+    }
+
+    /// Remove a callback set by `set_raw_report_hook`
+    pub fn clear_raw_report_hook(&mut self) {
+        self.core.set_raw_report_hook(None);
+    }
+
+    /// Read the product string and serial number of the device, eg to tell multiple
+    /// controllers apart
+    pub fn device_info(&self) -> Result<DeviceInfo, Error> {
+        read_device_info(&self.device, Self::VENDOR_ID, Self::PRODUCT_ID)
+    }
+
+    /// Write a complete frame of display data directly to the device
+    ///
+    /// The frame is 1024 bytes, 8 rows of 128 columns in row order, sent top to bottom as four
+    /// reports of two rows. Each byte is a column of 8 pixels with the least significant bit at
+    /// the top, the same layout as `MonochromeCanvas::data`. The display canvas is not updated,
+    /// so the next change to it replaces the raw frame. Nothing is written while the display is
+    /// off.
+    pub fn write_display_raw(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.core.write_display_raw(&self.device, data)
+    }
+
+    /// Clear the display and turn off all LEDs
+    ///
+    /// Called when the controller is dropped (ignoring any errors) so the device is left blank
+    /// when an application exits.
+    pub fn shutdown(&mut self) -> Result<(), Error> {
+        self.core.clear_display(&self.device, &mut self.display)?;
+
+        self.leds = [0; LED_COUNT];
+        self.leds_dirty = true;
+        self.leds_batched = false;
+        self.send_leds()
+    }
+
+    /// Determine if the graphics display is on
+    pub fn is_display_on(&self) -> bool {
+        self.core.is_display_on()
+    }
+
+    /// Send the display brightness if it has been changed
+    fn send_display_brightness(&mut self) -> Result<(), Error> {
+        if self.display_brightness_report && self.display_brightness_dirty {
+            // Cleared before the write so a device that rejects the report is not retried on
+            // every frame
+            self.display_brightness_dirty = false;
+            self.device
+                .write(&[DISPLAY_BRIGHTNESS_ADDR, self.display_brightness])
+                .map_err(Error::from_device_io)?;
         }
-        self.display.clear_dirty_flag();
 
         Ok(())
     }
 
-    /// Update LEDs if the array has been updated
+    /// Update LEDs if the array has been updated, unless a batch is in progress
     fn send_leds(&mut self) -> Result<(), Error> {
+        if self.leds_batched {
+            return Ok(());
+        }
         if self.leds_dirty {
             let mut buffer: Vec<u8> = vec![LED_ADDR];
-            buffer.extend_from_slice(&self.leds);
-            self.device.write(buffer.as_slice())?;
+            if self.led_brightness < 1.0 {
+                let brightness = self.led_brightness;
+                buffer.extend(self.leds.iter().map(|v| (*v as f32 * brightness) as u8));
+            } else {
+                buffer.extend_from_slice(&self.leds);
+            }
+            self.device
+                .write(buffer.as_slice())
+                .map_err(Error::from_device_io)?;
         }
         self.leds_dirty = false;
 
@@ -174,16 +497,12 @@ impl MaschineMikroMk2 {
     fn read(&mut self, context: &mut EventContext) -> Result<(), Error> {
         let mut buffer = [0u8; INPUT_BUFFER_SIZE];
 
-        for idx in 0..32 {
-            let bytes_read = match self.device.read(&mut buffer) {
-                Ok(n) => n,
-                Err(e) => return Err(Error::HidAPI(e)),
-            };
-
-            if bytes_read > 0 && buffer[0] == 0x01 {
-                self.process_buttons(&buffer[1..6], context)?;
-            } else if (bytes_read > 0) && (buffer[0] == 0x20) && ((idx % 7) == 0) {
-                self.process_pads(&buffer[1..], context)?;
+        for idx in 0..self.core.reports_per_read() {
+            match self.core.read_report(&self.device, &mut buffer, idx)? {
+                Report::End => break,
+                Report::Handled => {}
+                Report::Buttons(report) => self.process_buttons(report, context)?,
+                Report::Pads(report) => self.process_pads(report, context),
             }
         }
 
@@ -198,9 +517,14 @@ impl MaschineMikroMk2 {
 
         // Scan buttons
         for btn in BUTTON_SHIFT..BUTTON_NONE {
-            let button_pressed = is_button_pressed(&buffer, btn);
+            let button_pressed = is_button_pressed(buffer, btn);
             if button_pressed != self.button_states[btn as usize] {
                 self.button_states[btn as usize] = button_pressed;
+                self.button_pressed_at[btn as usize] = if button_pressed {
+                    Some(Instant::now())
+                } else {
+                    None
+                };
 
                 if btn == BUTTON_SHIFT {
                     self.shift_pressed = button_pressed;
@@ -222,73 +546,167 @@ impl MaschineMikroMk2 {
         // Handle encoder data
         let encoder_value = buffer[4];
         if self.encoder_value != encoder_value {
-            let direction = if ((self.encoder_value < encoder_value)
-                | ((self.encoder_value == 0x0f) && (encoder_value == 0x00)))
-                & (!((self.encoder_value == 0x00) & (encoder_value == 0x0f)))
-            {
-                Direction::Down
-            } else {
-                Direction::Up
-            };
+            let direction = encoder_direction(self.encoder_value, encoder_value);
             self.encoder_value = encoder_value;
-            context.add_event(Event::Encoder(0, direction, self.shift_pressed));
+            let steps = self.encoder_steps(Instant::now());
+            context.add_event(Event::Encoder(0, direction, steps, self.shift_pressed));
         }
 
         Ok(())
     }
 
-    /// Process a pads report message
-    fn process_pads(&mut self, buffer: &[u8], context: &mut EventContext) -> Result<(), Error> {
-        if buffer.len() < 64 {
-            return Err(Error::InvalidReport);
+    /// Report any buttons that have been held past the long press threshold
+    ///
+    /// Each press is only reported once, releasing the button resets the timer.
+    fn process_long_presses(&mut self, context: &mut EventContext) {
+        for btn in (BUTTON_SHIFT + 1)..BUTTON_NONE {
+            if let Some(pressed_at) = self.button_pressed_at[btn as usize] {
+                if pressed_at.elapsed() >= self.long_press_threshold {
+                    self.button_pressed_at[btn as usize] = None;
+                    let button = self.as_device_button(btn);
+                    context.add_event(Event::ButtonLongPress(button, self.shift_pressed));
+                }
+            }
         }
+    }
 
-        for idx in (0..32).step_by(2) {
-            let low_byte = buffer[idx];
-            let high_byte = buffer[idx + 1];
-            let pad = ((high_byte & 0xF0) >> 4) as usize;
-            let value = (((high_byte & 0x0F) as u16) << 8) | low_byte as u16;
-            let pressed = value > 512;
+    /// Determine the number of steps a detent represents based on the time since the last one
+    fn encoder_steps(&mut self, now: Instant) -> u8 {
+        let last = self.encoder_turned_at.replace(now);
+        if !self.encoder_acceleration {
+            return 1;
+        }
+
+        last.and_then(|last| {
+            let interval = now.duration_since(last);
+            ENCODER_ACCELERATION
+                .iter()
+                .find(|(limit, _)| interval <= *limit)
+                .map(|(_, steps)| *steps)
+        })
+        .unwrap_or(1)
+    }
+
+    /// Process a pads report message
+    fn process_pads(&mut self, buffer: &[u8], context: &mut EventContext) {
+        for (pad, value) in pad_report_values(buffer) {
+            let pressed = if self.pads_status[pad] {
+                value > self.pad_threshold.saturating_sub(self.pad_hysteresis)
+            } else {
+                value > self.pad_threshold
+            };
 
             self.pads_data[pad] = value;
-            if pressed | self.pads_status[pad] {
-                self.pads_status[pad] = pressed;
-                context.add_event(Event::Pad(
-                    pad as u8,
-                    if pressed { (value >> 4) as u8 } else { 0 },
-                    self.shift_pressed,
-                ));
+            if pressed && !self.pads_status[pad] {
+                let velocity = (value >> 4) as u8;
+                self.pads_status[pad] = true;
+                self.pads_aftertouch[pad] = value;
+                self.process_pad_strike(pad, velocity, context);
+                self.process_pad_tap(pad, velocity, context);
+                if let Some(colour) = self.pad_hit_feedback {
+                    self.set_pad_led(pad as u8, colour.scaled(velocity as f32 / 255.0));
+                }
+            } else if pressed {
+                if self.aftertouch {
+                    self.process_pad_aftertouch(pad, value, context);
+                }
+            } else if self.pads_status[pad] {
+                // Ensure a held back strike is reported before its release
+                if self.chord.iter().any(|(p, _)| *p == pad as u8) {
+                    self.flush_chord(context);
+                }
+                self.pads_status[pad] = false;
+                if self.pad_hit_feedback.is_some() {
+                    self.set_pad_led(pad as u8, Colour::BLACK);
+                }
+                context.add_event(Event::PadRelease(pad as u8, self.shift_pressed));
             }
         }
+    }
 
-        Ok(())
+    /// Report a pad strike, or hold it back as part of a chord if chord detection is enabled
+    fn process_pad_strike(&mut self, pad: usize, velocity: u8, context: &mut EventContext) {
+        if self.chord_window.is_some() {
+            if self.chord.is_empty() {
+                self.chord_started_at = Some(Instant::now());
+            }
+            self.chord.push((pad as u8, velocity));
+        } else {
+            context.add_event(Event::Pad(pad as u8, velocity, self.shift_pressed));
+        }
+    }
+
+    /// Report any held back pad strikes once the chord window has closed
+    fn process_chords(&mut self, context: &mut EventContext) {
+        if let (Some(started_at), Some(window)) = (self.chord_started_at, self.chord_window) {
+            if started_at.elapsed() >= window {
+                self.flush_chord(context);
+            }
+        } else if !self.chord.is_empty() {
+            // Chord detection was disabled while strikes were held back
+            self.flush_chord(context);
+        }
+    }
+
+    /// Report held back pad strikes as either a chord or individual strikes
+    fn flush_chord(&mut self, context: &mut EventContext) {
+        self.chord_started_at = None;
+        let chord: Vec<(u8, u8)> = self.chord.drain(..).collect();
+        if chord.len() >= self.chord_size {
+            context.add_event(Event::PadChord(chord, self.shift_pressed));
+        } else {
+            for (pad, velocity) in chord {
+                context.add_event(Event::Pad(pad, velocity, self.shift_pressed));
+            }
+        }
+    }
+
+    /// Track the strike of a pad and report a double tap if it follows a previous strike
+    ///
+    /// The regular pad event is always emitted, a double tap is reported in addition to it. A
+    /// third strike starts a new sequence rather than being reported as another double tap.
+    fn process_pad_tap(&mut self, pad: usize, velocity: u8, context: &mut EventContext) {
+        let now = Instant::now();
+        match self.pads_tapped_at[pad] {
+            Some(tapped_at) if now.duration_since(tapped_at) <= self.double_tap_interval => {
+                self.pads_tapped_at[pad] = None;
+                context.add_event(Event::PadDoubleTap(pad as u8, velocity, self.shift_pressed));
+            }
+            _ => self.pads_tapped_at[pad] = Some(now),
+        }
+    }
+
+    /// Report the pressure of a held pad if it has moved far enough from the last report
+    fn process_pad_aftertouch(&mut self, pad: usize, value: u16, context: &mut EventContext) {
+        if value.abs_diff(self.pads_aftertouch[pad]) > self.aftertouch_delta {
+            self.pads_aftertouch[pad] = value;
+            context.add_event(Event::PadAftertouch(pad as u8, value, self.shift_pressed));
+        }
     }
 
     /// Set the colour of an LED
     fn set_led(&mut self, led: u8, colour: Colour) {
         let base = led as usize;
 
-        if self.is_rgb_led(led) {
+        if Self::led_is_rgb(led) {
             let (r, g, b) = colour.components();
+            let r = self.gamma_table[r as usize] >> 1;
+            let g = self.gamma_table[g as usize] >> 1;
+            let b = self.gamma_table[b as usize] >> 1;
 
-            self.leds_dirty =
+            self.leds_dirty |=
                 (r != self.leds[base]) | (g != self.leds[base + 1]) | (b != self.leds[base + 2]);
 
-            self.leds[base] = r >> 1;
-            self.leds[base + 1] = g >> 1;
-            self.leds[base + 2] = b >> 1;
+            self.leds[base] = r;
+            self.leds[base + 1] = g;
+            self.leds[base + 2] = b;
         } else {
             let m = colour.as_1bit();
-            self.leds_dirty = m != self.leds[base];
+            self.leds_dirty |= m != self.leds[base];
             self.leds[base] = m;
         }
     }
 
-    /// Determine if an LED is RGB or Mono
-    fn is_rgb_led(&self, led: u8) -> bool {
-        (led == LED_GROUP) | (LED_PAD13..=LED_PAD04).contains(&led)
-    }
-
     /// Convert a button code into a button enum
     fn as_device_button(&self, button: u8) -> Button {
         match button {
@@ -324,6 +742,41 @@ impl MaschineMikroMk2 {
         }
     }
 
+    /// Convert a button enum into a button code
+    fn as_button_code(&self, button: Button) -> Option<u8> {
+        match button {
+            Button::Erase => Some(BUTTON_ERASE),
+            Button::Rec => Some(BUTTON_REC),
+            Button::Play => Some(BUTTON_PLAY),
+            Button::Grid => Some(BUTTON_GRID),
+            Button::TransportRight => Some(BUTTON_TRANSPORT_RIGHT),
+            Button::TransportLeft => Some(BUTTON_TRANSPORT_LEFT),
+            Button::Restart => Some(BUTTON_RESTART),
+            Button::MainEncoder => Some(BUTTON_MAIN_ENCODER),
+            Button::NoteRepeat => Some(BUTTON_NOTE_REPEAT),
+            Button::Sampling => Some(BUTTON_SAMPLING),
+            Button::Browse => Some(BUTTON_BROWSE),
+            Button::Group => Some(BUTTON_GROUP),
+            Button::Main => Some(BUTTON_MAIN),
+            Button::BrowseRight => Some(BUTTON_BROWSE_RIGHT),
+            Button::BrowseLeft => Some(BUTTON_BROWSE_LEFT),
+            Button::Nav => Some(BUTTON_NAV),
+            Button::Control => Some(BUTTON_CONTROL),
+            Button::F3 => Some(BUTTON_F3),
+            Button::F2 => Some(BUTTON_F2),
+            Button::F1 => Some(BUTTON_F1),
+            Button::Mute => Some(BUTTON_MUTE),
+            Button::Solo => Some(BUTTON_SOLO),
+            Button::Select => Some(BUTTON_SELECT),
+            Button::Duplicate => Some(BUTTON_DUPLICATE),
+            Button::View => Some(BUTTON_VIEW),
+            Button::PadMode => Some(BUTTON_PAD_MODE),
+            Button::Pattern => Some(BUTTON_PATTERN),
+            Button::Scene => Some(BUTTON_SCENE),
+            Button::Unknown => None,
+        }
+    }
+
     /// Convert a button into a LED index
     fn button_to_led(&self, button: Button) -> Option<u8> {
         match button {
@@ -382,6 +835,229 @@ impl MaschineMikroMk2 {
     }
 }
 
+///
+/// Collects configuration for a Maschine Mikro Mk2 and applies it when the controller is built
+///
+/// ```no_run
+/// use maschine::devices::MaschineMikroMk2;
+///
+/// let api = hidapi::HidApi::new().unwrap();
+/// let device = api
+///     .open(MaschineMikroMk2::VENDOR_ID, MaschineMikroMk2::PRODUCT_ID)
+///     .unwrap();
+/// let controller = MaschineMikroMk2::builder()
+///     .pad_threshold(600)
+///     .brightness(0.5)
+///     .build(device)
+///     .unwrap();
+/// ```
+///
+/// Each option matches a setter on `MaschineMikroMk2`, options that are not set keep the
+/// controller defaults.
+///
+#[derive(Debug, Clone, Default)]
+pub struct MaschineMikroMk2Builder {
+    pad_threshold: Option<u16>,
+    pad_hysteresis: Option<u16>,
+    brightness: Option<f32>,
+    display_brightness: Option<u8>,
+    display_brightness_report: Option<bool>,
+    gamma: Option<f32>,
+    tick_policy: Option<TickPolicy>,
+    target_fps: Option<u32>,
+    read_timeout: Option<i32>,
+    reports_per_read: Option<usize>,
+    pad_report_interval: Option<usize>,
+    long_press_threshold: Option<Duration>,
+    double_tap_interval: Option<Duration>,
+    aftertouch: Option<bool>,
+    aftertouch_delta: Option<u16>,
+    chord_window: Option<Duration>,
+    chord_size: Option<usize>,
+    encoder_acceleration: Option<bool>,
+    pad_hit_feedback: Option<Colour>,
+}
+
+impl MaschineMikroMk2Builder {
+    /// Pressure a pad must exceed to be struck
+    pub fn pad_threshold(mut self, threshold: u16) -> Self {
+        self.pad_threshold = Some(threshold);
+        self
+    }
+
+    /// Margin below the threshold a held pad must fall to be released
+    pub fn pad_hysteresis(mut self, margin: u16) -> Self {
+        self.pad_hysteresis = Some(margin);
+        self
+    }
+
+    /// Brightness factor applied to all LEDs (0.0 - 1.0)
+    pub fn brightness(mut self, factor: f32) -> Self {
+        self.brightness = Some(factor);
+        self
+    }
+
+    /// Brightness of the graphics display
+    pub fn display_brightness(mut self, level: u8) -> Self {
+        self.display_brightness = Some(level);
+        self
+    }
+
+    /// Send the display brightness to the device
+    pub fn display_brightness_report(mut self, enabled: bool) -> Self {
+        self.display_brightness_report = Some(enabled);
+        self
+    }
+
+    /// Gamma correction applied to RGB LEDs
+    pub fn gamma(mut self, gamma: f32) -> Self {
+        self.gamma = Some(gamma);
+        self
+    }
+
+    /// Scheduling of device IO across calls to `tick`
+    pub fn tick_policy(mut self, policy: TickPolicy) -> Self {
+        self.tick_policy = Some(policy);
+        self
+    }
+
+    /// Maximum rate the display is written at, 0 is unlimited
+    pub fn target_fps(mut self, fps: u32) -> Self {
+        self.target_fps = Some(fps);
+        self
+    }
+
+    /// Timeout in milliseconds when reading reports
+    pub fn read_timeout(mut self, ms: i32) -> Self {
+        self.read_timeout = Some(ms);
+        self
+    }
+
+    /// Maximum number of reports drained per read
+    pub fn reports_per_read(mut self, reports: usize) -> Self {
+        self.reports_per_read = Some(reports);
+        self
+    }
+
+    /// Interval at which pad reports are processed
+    pub fn pad_report_interval(mut self, interval: usize) -> Self {
+        self.pad_report_interval = Some(interval);
+        self
+    }
+
+    /// Time a button is held before a long press is reported
+    pub fn long_press_threshold(mut self, threshold: Duration) -> Self {
+        self.long_press_threshold = Some(threshold);
+        self
+    }
+
+    /// Maximum time between strikes of a double tap
+    pub fn double_tap_interval(mut self, interval: Duration) -> Self {
+        self.double_tap_interval = Some(interval);
+        self
+    }
+
+    /// Report pressure changes of held pads
+    pub fn aftertouch(mut self, enabled: bool) -> Self {
+        self.aftertouch = Some(enabled);
+        self
+    }
+
+    /// Minimum change in pressure reported as aftertouch
+    pub fn aftertouch_delta(mut self, delta: u16) -> Self {
+        self.aftertouch_delta = Some(delta);
+        self
+    }
+
+    /// Window in which pad strikes are grouped into a chord
+    pub fn chord_window(mut self, window: Duration) -> Self {
+        self.chord_window = Some(window);
+        self
+    }
+
+    /// Minimum number of pads struck to report a chord
+    pub fn chord_size(mut self, size: usize) -> Self {
+        self.chord_size = Some(size);
+        self
+    }
+
+    /// Report fast encoder turns as multiple steps
+    pub fn encoder_acceleration(mut self, enabled: bool) -> Self {
+        self.encoder_acceleration = Some(enabled);
+        self
+    }
+
+    /// Light pads in a colour as they are struck
+    pub fn pad_hit_feedback(mut self, colour: Colour) -> Self {
+        self.pad_hit_feedback = Some(colour);
+        self
+    }
+
+    /// Create a controller from a device with the collected configuration applied
+    pub fn build(self, device: HidDevice) -> Result<MaschineMikroMk2, Error> {
+        let mut controller = MaschineMikroMk2::new(device);
+        if let Some(threshold) = self.pad_threshold {
+            controller.set_pad_threshold(threshold);
+        }
+        if let Some(margin) = self.pad_hysteresis {
+            controller.set_pad_hysteresis(margin);
+        }
+        if let Some(factor) = self.brightness {
+            controller.set_led_brightness(factor);
+        }
+        if let Some(level) = self.display_brightness {
+            controller.set_display_brightness(level);
+        }
+        if let Some(enabled) = self.display_brightness_report {
+            controller.set_display_brightness_report(enabled);
+        }
+        if let Some(gamma) = self.gamma {
+            controller.set_gamma(gamma);
+        }
+        if let Some(policy) = self.tick_policy {
+            controller.set_tick_policy(policy);
+        }
+        if let Some(fps) = self.target_fps {
+            controller.set_target_fps(fps);
+        }
+        if let Some(ms) = self.read_timeout {
+            controller.set_read_timeout(ms)?;
+        }
+        if let Some(reports) = self.reports_per_read {
+            controller.set_reports_per_read(reports);
+        }
+        if let Some(interval) = self.pad_report_interval {
+            controller.set_pad_report_interval(interval);
+        }
+        if let Some(threshold) = self.long_press_threshold {
+            controller.set_long_press_threshold(threshold);
+        }
+        if let Some(interval) = self.double_tap_interval {
+            controller.set_double_tap_interval(interval);
+        }
+        if let Some(enabled) = self.aftertouch {
+            controller.set_aftertouch(enabled);
+        }
+        if let Some(delta) = self.aftertouch_delta {
+            controller.set_aftertouch_delta(delta);
+        }
+        if let Some(window) = self.chord_window {
+            controller.set_chord_window(Some(window));
+        }
+        if let Some(size) = self.chord_size {
+            controller.set_chord_size(size);
+        }
+        if let Some(enabled) = self.encoder_acceleration {
+            controller.set_encoder_acceleration(enabled);
+        }
+        if let Some(colour) = self.pad_hit_feedback {
+            controller.set_pad_hit_feedback(Some(colour));
+        }
+
+        Ok(controller)
+    }
+}
+
 impl Controller for MaschineMikroMk2 {
     fn set_button_led(&mut self, button: Button, colour: Colour) {
         match self.button_to_led(button) {
@@ -396,25 +1072,64 @@ impl Controller for MaschineMikroMk2 {
             None => (),
         };
     }
+
+    fn set_all_leds(&mut self, colour: Colour) {
+        let mut led = 0;
+        while (led as usize) < LED_COUNT {
+            self.set_led(led, colour);
+            led += if Self::led_is_rgb(led) { 3 } else { 1 };
+        }
+    }
+
+    fn is_button_led_rgb(&self, button: Button) -> bool {
+        self.button_to_led(button).is_some_and(Self::led_is_rgb)
+    }
+
+    fn is_pad_led_rgb(&self) -> bool {
+        true
+    }
+
+    fn begin_led_batch(&mut self) {
+        self.leds_batched = true;
+    }
+
+    fn flush_leds(&mut self) -> Result<(), Error> {
+        self.leds_batched = false;
+        self.send_leds()
+    }
 }
 
 impl EventTask for MaschineMikroMk2 {
     fn tick(&mut self, context: &mut EventContext) -> Result<(), Error> {
-        if self.tick_state == 0 {
-            self.send_frame()?;
-        } else if self.tick_state == 1 {
-            self.send_leds()?;
-        } else if self.tick_state == 2 {
-            self.read(context)?;
+        for task in self.core.next_tick() {
+            match task {
+                TickTask::Frame => {
+                    self.send_display_brightness()?;
+                    self.core.send_frame(&self.device, &mut self.display)?;
+                }
+                TickTask::Leds => self.send_leds()?,
+                TickTask::Read => self.read(context)?,
+            }
         }
 
-        self.tick_state = (self.tick_state + 1) % 3;
+        self.process_long_presses(context);
+        self.process_chords(context);
 
         Ok(())
     }
 }
 
-fn is_button_pressed(buffer: &[u8], button: u8) -> bool {
-    let byte_idx = (button >> 3) as usize;
-    (buffer[byte_idx] & (1 << (button % 8))) != 0
+impl Drop for MaschineMikroMk2 {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+/// Build a lookup table mapping a linear channel value to a gamma corrected one
+fn gamma_table(gamma: f32) -> [u8; 256] {
+    let mut table = [0u8; 256];
+    for (idx, value) in table.iter_mut().enumerate() {
+        *value = ((idx as f32 / 255.0).powf(gamma) * 255.0).round() as u8;
+    }
+    table
 }
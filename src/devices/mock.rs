@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+
+use crate::colour::Colour;
+use crate::controller::Controller;
+use crate::display::MonochromeCanvas;
+use crate::error::Error;
+use crate::events::{Button, Direction, Event, EventContext, EventTask};
+
+const PAD_COUNT: usize = 16;
+
+///
+/// Headless controller for developing and testing without a device connected
+///
+/// Drawing goes to an in-memory display and LED changes are recorded so they can be checked
+/// after the code under test has run. Input is simulated by queueing events that are added
+/// to the context on the next call to `tick`.
+///
+/// ```
+/// use maschine::devices::MockController;
+/// use maschine::{Colour, Controller, Event, EventContext, EventTask};
+///
+/// let mut ctlr = MockController::new();
+/// ctlr.strike_pad(3, 100);
+///
+/// let mut context = EventContext::new();
+/// ctlr.tick(&mut context).unwrap();
+/// for event in context.drain() {
+///     if let Event::Pad(pad, _, _) = event {
+///         ctlr.set_pad_led(pad, Colour::RED);
+///     }
+/// }
+///
+/// assert_eq!(ctlr.pad_led(3), Some(Colour::RED));
+/// ```
+///
+pub struct MockController {
+    pub display: MonochromeCanvas,
+    pad_leds: [Colour; PAD_COUNT],
+    button_leds: Vec<(Button, Colour)>,
+    all_leds: Colour,
+    pending: VecDeque<Event>,
+    shift_pressed: bool,
+    ticks: usize,
+}
+
+impl Default for MockController {
+    fn default() -> Self {
+        MockController {
+            display: MonochromeCanvas::new(128, 64),
+            pad_leds: [Colour::BLACK; PAD_COUNT],
+            button_leds: Vec::new(),
+            all_leds: Colour::BLACK,
+            pending: VecDeque::new(),
+            shift_pressed: false,
+            ticks: 0,
+        }
+    }
+}
+
+impl MockController {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Queue an event to be generated by the next tick
+    pub fn inject(&mut self, event: Event) {
+        self.pending.push_back(event);
+    }
+
+    /// Set the state of the shift button reported with following simulated input
+    pub fn set_shift(&mut self, pressed: bool) {
+        self.shift_pressed = pressed;
+    }
+
+    /// Simulate a button being pressed or released
+    pub fn press_button(&mut self, button: Button, pressed: bool) {
+        self.inject(Event::Button(button, pressed, self.shift_pressed));
+    }
+
+    /// Simulate a pad being struck
+    pub fn strike_pad(&mut self, pad: u8, velocity: u8) {
+        self.inject(Event::Pad(pad, velocity, self.shift_pressed));
+    }
+
+    /// Simulate a pad being released
+    pub fn release_pad(&mut self, pad: u8) {
+        self.inject(Event::PadRelease(pad, self.shift_pressed));
+    }
+
+    /// Simulate the encoder being turned
+    pub fn turn_encoder(&mut self, direction: Direction, steps: u8) {
+        self.inject(Event::Encoder(0, direction, steps, self.shift_pressed));
+    }
+
+    /// Colour last applied to a pad LED
+    pub fn pad_led(&self, pad: u8) -> Option<Colour> {
+        self.pad_leds.get(pad as usize).copied()
+    }
+
+    /// Colour last applied to a button LED, either directly or by `set_all_leds` (black if
+    /// neither has been called)
+    pub fn button_led(&self, button: Button) -> Colour {
+        self.button_leds
+            .iter()
+            .find(|(led, _)| *led == button)
+            .map_or(self.all_leds, |(_, colour)| *colour)
+    }
+
+    /// Number of times the controller has been ticked
+    pub fn ticks(&self) -> usize {
+        self.ticks
+    }
+}
+
+impl Controller for MockController {
+    fn set_button_led(&mut self, button: Button, colour: Colour) {
+        match self.button_leds.iter_mut().find(|(led, _)| *led == button) {
+            Some(entry) => entry.1 = colour,
+            None => self.button_leds.push((button, colour)),
+        }
+    }
+
+    fn set_pad_led(&mut self, pad: u8, colour: Colour) {
+        if let Some(led) = self.pad_leds.get_mut(pad as usize) {
+            *led = colour;
+        }
+    }
+
+    fn set_all_leds(&mut self, colour: Colour) {
+        self.pad_leds = [colour; PAD_COUNT];
+        self.button_leds.clear();
+        self.all_leds = colour;
+    }
+}
+
+impl EventTask for MockController {
+    fn tick(&mut self, context: &mut EventContext) -> Result<(), Error> {
+        self.ticks += 1;
+        while let Some(event) = self.pending.pop_front() {
+            context.add_event(event);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::{Canvas, Pixel};
+
+    #[test]
+    fn injected_events_are_generated_by_tick() {
+        let mut ctlr = MockController::new();
+        ctlr.set_shift(true);
+        ctlr.press_button(Button::Play, true);
+        ctlr.turn_encoder(Direction::Up, 2);
+
+        let mut context = EventContext::new();
+        ctlr.tick(&mut context).unwrap();
+        let events: Vec<Event> = context.drain().collect();
+
+        assert_eq!(
+            events,
+            vec![
+                Event::Button(Button::Play, true, true),
+                Event::Encoder(0, Direction::Up, 2, true),
+            ]
+        );
+
+        ctlr.tick(&mut context).unwrap();
+        assert!(context.events.is_empty());
+        assert_eq!(ctlr.ticks(), 2);
+    }
+
+    #[test]
+    fn leds_and_display_are_recorded() {
+        let mut ctlr = MockController::new();
+        ctlr.set_button_led(Button::Rec, Colour::RED);
+        ctlr.set_pad_led(15, Colour::BLUE);
+        ctlr.set_pad_led(16, Colour::BLUE);
+        ctlr.display.set_pixel(0, 0, Pixel::On);
+
+        assert_eq!(ctlr.button_led(Button::Rec), Colour::RED);
+        assert_eq!(ctlr.button_led(Button::Play), Colour::BLACK);
+        assert_eq!(ctlr.pad_led(15), Some(Colour::BLUE));
+        assert_eq!(ctlr.pad_led(16), None);
+        assert_eq!(ctlr.display.data()[0], 0x01);
+
+        ctlr.set_all_leds(Colour::BLACK);
+        assert_eq!(ctlr.button_led(Button::Rec), Colour::BLACK);
+    }
+
+    #[test]
+    fn set_all_leds_applies_to_unset_buttons() {
+        let mut ctlr = MockController::new();
+        ctlr.set_all_leds(Colour::RED);
+        assert_eq!(ctlr.button_led(Button::Play), Colour::RED);
+        assert_eq!(ctlr.pad_led(0), Some(Colour::RED));
+
+        ctlr.set_button_led(Button::Play, Colour::GREEN);
+        assert_eq!(ctlr.button_led(Button::Play), Colour::GREEN);
+        assert_eq!(ctlr.button_led(Button::Rec), Colour::RED);
+    }
+
+    #[test]
+    fn pad_leds_set_by_grid_coordinate() {
+        let mut ctlr = MockController::new();
+        ctlr.set_pad_led_xy(1, 2, Colour::GREEN);
+        ctlr.set_pad_led_xy(4, 0, Colour::RED);
+
+        assert_eq!(ctlr.pad_led(0x9), Some(Colour::GREEN));
+        assert!((0..16).all(|pad| ctlr.pad_led(pad) != Some(Colour::RED)));
+    }
+}
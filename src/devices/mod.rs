@@ -1,3 +1,586 @@
+use hidapi::{HidApi, HidDevice};
+use std::cmp::min;
+use std::ffi::CString;
+use std::time::{Duration, Instant};
+
+use crate::controller::{Controller, TickPolicy};
+use crate::display::{Canvas, MonochromeCanvas, Pixel};
+use crate::error::Error;
+use crate::events::Direction;
+
+mod maschine_mikro_mk1;
 mod maschine_mikro_mk2;
+mod mock;
+
+pub use maschine_mikro_mk1::MaschineMikroMk1;
+pub use maschine_mikro_mk2::{MaschineMikroMk2, MaschineMikroMk2Builder};
+pub use mock::MockController;
+
+const DISPLAY_ADDR: u8 = 0xE0;
+const DISPLAY_HEADER_SIZE: usize = 9;
+// The number of referenced bytes must be <= 256
+// Eg Column width * number of rows
+const DISPLAY_BAND_SIZE: usize = 256;
+// Complete 128x64 1bpp frame
+const DISPLAY_DATA_SIZE: usize = 1024;
+
+// Pads are arranged in a 4x4 grid
+const PAD_COLUMNS: u8 = 4;
+const PAD_ROWS: u8 = 4;
+
+// Size of the buffer reports are read into
+const INPUT_BUFFER_SIZE: usize = 512;
+
+// Maximum number of reports drained from the device by a single read
+const DEFAULT_REPORTS_PER_READ: usize = 32;
+// Pad reports stream continuously while the device is in use and each carries the state of
+// every pad, so only those read on every nth iteration of a read are processed to limit the
+// cost of handling them. Pad changes are delayed by skipped reports rather than lost, unless a
+// strike starts and ends between two processed reports.
+const DEFAULT_PAD_REPORT_INTERVAL: usize = 7;
+
+// Pressure values of all pads at the start of a pad report, 2 bytes per pad
+const PAD_REPORT_SIZE: usize = 32;
+
+// Callback passed reports that are not recognised
+type RawReportHook = Box<dyn FnMut(&[u8]) + Send>;
+
+// Supported controllers (Vendor ID, Product ID, Name)
+const SUPPORTED_DEVICES: [(u16, u16, &str); 2] = [
+    (
+        MaschineMikroMk2::VENDOR_ID,
+        MaschineMikroMk2::PRODUCT_ID,
+        "Maschine Mikro Mk2",
+    ),
+    (
+        MaschineMikroMk1::VENDOR_ID,
+        MaschineMikroMk1::PRODUCT_ID,
+        "Maschine Mikro Mk1",
+    ),
+];
+
+///
+/// Identifying information of a connected controller
+///
+#[derive(Debug, Clone)]
+pub struct DeviceInfo {
+    pub name: &'static str,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    /// Product string reported by the device
+    pub product: Option<String>,
+    pub serial_number: Option<String>,
+    /// Platform path used to open the device, empty when read from an already open device
+    pub path: CString,
+}
+
+///
+/// Scan for all connected controllers supported by this crate
+///
+pub fn enumerate(hid_api: &HidApi) -> Vec<DeviceInfo> {
+    hid_api
+        .device_list()
+        .filter_map(|info| {
+            SUPPORTED_DEVICES
+                .iter()
+                .find(|(vendor_id, product_id, _)| {
+                    *vendor_id == info.vendor_id() && *product_id == info.product_id()
+                })
+                .map(|(vendor_id, product_id, name)| DeviceInfo {
+                    name,
+                    vendor_id: *vendor_id,
+                    product_id: *product_id,
+                    product: info.product_string().map(String::from),
+                    serial_number: info.serial_number().map(String::from),
+                    path: info.path().to_owned(),
+                })
+        })
+        .collect()
+}
+
+///
+/// Open a controller found by `enumerate`
+///
+pub fn open_device(hid_api: &HidApi, info: &DeviceInfo) -> Result<Box<dyn Controller>, Error> {
+    match (info.vendor_id, info.product_id) {
+        (MaschineMikroMk2::VENDOR_ID, MaschineMikroMk2::PRODUCT_ID) => Ok(Box::new(
+            MaschineMikroMk2::new(hid_api.open_path(&info.path)?),
+        )),
+        (MaschineMikroMk1::VENDOR_ID, MaschineMikroMk1::PRODUCT_ID) => Ok(Box::new(
+            MaschineMikroMk1::new(hid_api.open_path(&info.path)?),
+        )),
+        _ => Err(Error::UnsupportedDevice),
+    }
+}
+
+///
+/// Convert a pad number into a grid coordinate (column, row)
+///
+/// Pad numbers run left to right from the top row of the 4x4 grid, so pad 0x0 is the top left
+/// pad (labelled 13 on the device) and pad 0xF the bottom right (labelled 4). Row 0 is the top
+/// row. Pad numbers beyond 0xF are wrapped onto the grid.
+///
+pub fn pad_to_xy(pad: u8) -> (u8, u8) {
+    let pad = pad & 0x0F;
+    (pad % PAD_COLUMNS, pad / PAD_COLUMNS)
+}
+
+///
+/// Convert a grid coordinate (column, row) into a pad number, see `pad_to_xy`
+///
+pub fn xy_to_pad(x: u8, y: u8) -> Option<u8> {
+    if x < PAD_COLUMNS && y < PAD_ROWS {
+        Some(y * PAD_COLUMNS + x)
+    } else {
+        None
+    }
+}
+
+///
+/// State and device IO shared by the Maschine Mikro controllers
+///
+/// Covers scheduling of IO across ticks, frame rate limiting, writing the display and reading
+/// reports. Each controller adds the parsing of its reports and the layout of its LEDs.
+///
+pub(crate) struct DeviceCore {
+    tick_state: u8,
+    tick_policy: TickPolicy,
+    frame_interval: Option<Duration>,
+    frame_due_at: Option<Instant>,
+    display_on: bool,
+    read_timeout: i32,
+    reports_per_read: usize,
+    pad_report_interval: usize,
+    raw_report_hook: Option<RawReportHook>,
+}
+
+/// Device IO performed by a tick
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum TickTask {
+    Frame,
+    Leds,
+    Read,
+}
+
+/// Report read by `DeviceCore::read_report`
+pub(crate) enum Report<'a> {
+    /// No further reports are available
+    End,
+    /// Report has been dealt with (skipped or passed to the raw report hook)
+    Handled,
+    /// Buttons and encoder report, without the report ID
+    Buttons(&'a [u8]),
+    /// Pads report, without the report ID
+    Pads(&'a [u8]),
+}
+
+impl DeviceCore {
+    pub(crate) fn new() -> Self {
+        DeviceCore {
+            tick_state: 0,
+            tick_policy: TickPolicy::default(),
+            frame_interval: None,
+            frame_due_at: None,
+            display_on: true,
+            read_timeout: -1,
+            reports_per_read: DEFAULT_REPORTS_PER_READ,
+            pad_report_interval: DEFAULT_PAD_REPORT_INTERVAL,
+            raw_report_hook: None,
+        }
+    }
+
+    pub(crate) fn set_tick_policy(&mut self, policy: TickPolicy) {
+        self.tick_policy = policy;
+    }
+
+    pub(crate) fn set_read_timeout(&mut self, device: &HidDevice, ms: i32) -> Result<(), Error> {
+        device.set_blocking_mode(ms != 0)?;
+        self.read_timeout = ms;
+
+        Ok(())
+    }
+
+    pub(crate) fn set_target_fps(&mut self, fps: u32) {
+        self.frame_interval = frame_interval(fps);
+        self.frame_due_at = None;
+    }
+
+    pub(crate) fn set_reports_per_read(&mut self, reports: usize) {
+        self.reports_per_read = reports;
+    }
+
+    pub(crate) fn set_pad_report_interval(&mut self, interval: usize) {
+        self.pad_report_interval = interval.max(1);
+    }
+
+    pub(crate) fn set_raw_report_hook(&mut self, hook: Option<RawReportHook>) {
+        self.raw_report_hook = hook;
+    }
+
+    pub(crate) fn set_display_on(
+        &mut self,
+        device: &HidDevice,
+        display: &mut MonochromeCanvas,
+        on: bool,
+    ) -> Result<(), Error> {
+        if on == self.display_on {
+            return Ok(());
+        }
+
+        self.display_on = on;
+        if on {
+            display.set_dirty_flag();
+        } else {
+            write_display(device, &MonochromeCanvas::new(128, 64))?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn is_display_on(&self) -> bool {
+        self.display_on
+    }
+
+    /// Write a complete frame of display data, nothing is written while the display is off
+    pub(crate) fn write_display_raw(&self, device: &HidDevice, data: &[u8]) -> Result<(), Error> {
+        if data.len() != DISPLAY_DATA_SIZE {
+            return Err(Error::InvalidFrame);
+        }
+        if !self.display_on {
+            return Ok(());
+        }
+
+        write_display_data(device, data, |_| true)
+    }
+
+    /// Write the display if it has been changed and a frame is due
+    ///
+    /// The dirty flag is held while the display is off or a frame is not due so the frame is
+    /// sent on a later tick.
+    pub(crate) fn send_frame(
+        &mut self,
+        device: &HidDevice,
+        display: &mut MonochromeCanvas,
+    ) -> Result<(), Error> {
+        if !self.display_on || !display.is_dirty() {
+            return Ok(());
+        }
+        if !frame_due(self.frame_interval, &mut self.frame_due_at, Instant::now()) {
+            return Ok(());
+        }
+
+        write_display(device, display)?;
+        display.clear_dirty_flag();
+
+        Ok(())
+    }
+
+    /// Blank the display, the display is written even if it is off
+    pub(crate) fn clear_display(
+        &mut self,
+        device: &HidDevice,
+        display: &mut MonochromeCanvas,
+    ) -> Result<(), Error> {
+        display.fill(Pixel::Off);
+        display.set_dirty_flag();
+        write_display(device, display)?;
+        display.clear_dirty_flag();
+
+        Ok(())
+    }
+
+    /// Device IO to perform on this tick according to the tick policy
+    pub(crate) fn next_tick(&mut self) -> &'static [TickTask] {
+        let tasks: &'static [TickTask] = match self.tick_policy {
+            TickPolicy::RoundRobin => match self.tick_state {
+                0 => &[TickTask::Frame],
+                1 => &[TickTask::Leds],
+                _ => &[TickTask::Read],
+            },
+            TickPolicy::ReadEveryTick => &[TickTask::Read, TickTask::Frame, TickTask::Leds],
+        };
+        self.tick_state = (self.tick_state + 1) % 3;
+
+        tasks
+    }
+
+    /// Maximum number of reports to read in a single read
+    pub(crate) fn reports_per_read(&self) -> usize {
+        self.reports_per_read
+    }
+
+    /// Read the nth report of a read into a buffer
+    ///
+    /// Pad reports not on the pad report interval are skipped, unrecognised reports are passed
+    /// to the raw report hook.
+    pub(crate) fn read_report<'a>(
+        &mut self,
+        device: &HidDevice,
+        buffer: &'a mut [u8; INPUT_BUFFER_SIZE],
+        idx: usize,
+    ) -> Result<Report<'a>, Error> {
+        let bytes_read = device
+            .read_timeout(buffer, self.read_timeout)
+            .map_err(Error::from_device_io)?;
+
+        // No further input available within the timeout
+        if bytes_read == 0 {
+            return Ok(Report::End);
+        }
+
+        Ok(match buffer[0] {
+            0x01 => Report::Buttons(&buffer[1..6]),
+            0x20 => {
+                if idx.checked_rem(self.pad_report_interval) == Some(0) {
+                    Report::Pads(&buffer[1..bytes_read])
+                } else {
+                    Report::Handled
+                }
+            }
+            _ => {
+                if let Some(hook) = self.raw_report_hook.as_mut() {
+                    hook(&buffer[..bytes_read]);
+                }
+                Report::Handled
+            }
+        })
+    }
+}
+
+/// Read the identifying information of an open controller
+fn read_device_info(
+    device: &HidDevice,
+    vendor_id: u16,
+    product_id: u16,
+) -> Result<DeviceInfo, Error> {
+    let name = SUPPORTED_DEVICES
+        .iter()
+        .find(|(vid, pid, _)| *vid == vendor_id && *pid == product_id)
+        .map_or("Unknown", |(_, _, name)| name);
+
+    Ok(DeviceInfo {
+        name,
+        vendor_id,
+        product_id,
+        product: device.get_product_string().map_err(Error::from_device_io)?,
+        serial_number: device
+            .get_serial_number_string()
+            .map_err(Error::from_device_io)?,
+        path: CString::default(),
+    })
+}
+
+/// Write a 128x64 display to the device in 4 bands of 2 rows, bands without any changed rows
+/// are skipped
+fn write_display(device: &HidDevice, display: &MonochromeCanvas) -> Result<(), Error> {
+    write_display_data(device, display.data(), |row| display.is_row_dirty(row))
+}
+
+/// Write a complete frame of display data to the device, only rows reported as dirty are sent
+fn write_display_data<F>(device: &HidDevice, data: &[u8], is_row_dirty: F) -> Result<(), Error>
+where
+    F: Fn(usize) -> bool,
+{
+    if data.len() != DISPLAY_DATA_SIZE {
+        return Err(Error::InvalidFrame);
+    }
+
+    // Report is reused for each band so a frame is written without allocating
+    let mut buffer = [0u8; DISPLAY_HEADER_SIZE + DISPLAY_BAND_SIZE];
+    buffer[..DISPLAY_HEADER_SIZE].copy_from_slice(&[
+        DISPLAY_ADDR,
+        0x00, // Column offset
+        0x00, // ?
+        0x00, // Row (a row is 8 pixels high)
+        0x00, // ?
+        0x80, // Columns per row, 128 is full width
+        0x00, // ?
+        0x02, // Number of rows
+        0x00, // ?
+    ]);
+
+    for row in (0..8).step_by(2) {
+        if !(is_row_dirty(row) || is_row_dirty(row + 1)) {
+            continue;
+        }
+
+        let x_offset = row * 128;
+        buffer[3] = row as u8;
+        buffer[DISPLAY_HEADER_SIZE..]
+            .copy_from_slice(&data[x_offset..(x_offset + DISPLAY_BAND_SIZE)]);
+        device.write(&buffer).map_err(Error::from_device_io)?;
+    }
+
+    Ok(())
+}
+
+/// Decode the pad number and 12bit pressure of each pad in a pad report, a truncated report
+/// has no values so it is skipped rather than failing the read
+fn pad_report_values(buffer: &[u8]) -> impl Iterator<Item = (usize, u16)> + '_ {
+    buffer
+        .get(..PAD_REPORT_SIZE)
+        .unwrap_or_default()
+        .chunks_exact(2)
+        .map(|pair| {
+            let pad = ((pair[1] & 0xF0) >> 4) as usize;
+            let value = (((pair[1] & 0x0F) as u16) << 8) | pair[0] as u16;
+            (pad, value)
+        })
+}
+
+/// Scale a 12bit pad pressure to 0.0-1.0 with the press threshold as the floor
+fn normalize_pad_pressure(value: u16, threshold: u16) -> f32 {
+    const PAD_PRESSURE_MAX: u16 = 0x0FFF;
+
+    if value <= threshold || threshold >= PAD_PRESSURE_MAX {
+        return 0.0;
+    }
+    (min(value, PAD_PRESSURE_MAX) - threshold) as f32 / (PAD_PRESSURE_MAX - threshold) as f32
+}
+
+/// Interval between frames for a target frame rate, 0 is unlimited
+fn frame_interval(fps: u32) -> Option<Duration> {
+    if fps == 0 {
+        None
+    } else {
+        Some(Duration::from_secs(1) / fps)
+    }
+}
+
+/// Determine if a frame can be sent when the frame rate is limited, when it can the frame after
+/// it is scheduled
+fn frame_due(interval: Option<Duration>, due_at: &mut Option<Instant>, now: Instant) -> bool {
+    let Some(interval) = interval else {
+        return true;
+    };
+
+    if due_at.is_some_and(|due_at| due_at > now) {
+        return false;
+    }
+    *due_at = Some(now + interval);
+    true
+}
+
+/// Determine the direction of a turn of a 4bit encoder from its previous value
+fn encoder_direction(previous: u8, current: u8) -> Direction {
+    if ((previous < current) | ((previous == 0x0f) && (current == 0x00)))
+        & (!((previous == 0x00) & (current == 0x0f)))
+    {
+        Direction::Down
+    } else {
+        Direction::Up
+    }
+}
+
+/// Determine if a button is pressed in a button report
+fn is_button_pressed(buffer: &[u8], button: u8) -> bool {
+    let byte_idx = (button >> 3) as usize;
+    (buffer[byte_idx] & (1 << (button % 8))) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (Pad, Column, Row, Label printed on the device)
+    const PAD_GRID: [(u8, u8, u8, u8); 16] = [
+        (0x0, 0, 0, 13),
+        (0x1, 1, 0, 14),
+        (0x2, 2, 0, 15),
+        (0x3, 3, 0, 16),
+        (0x4, 0, 1, 9),
+        (0x5, 1, 1, 10),
+        (0x6, 2, 1, 11),
+        (0x7, 3, 1, 12),
+        (0x8, 0, 2, 5),
+        (0x9, 1, 2, 6),
+        (0xA, 2, 2, 7),
+        (0xB, 3, 2, 8),
+        (0xC, 0, 3, 1),
+        (0xD, 1, 3, 2),
+        (0xE, 2, 3, 3),
+        (0xF, 3, 3, 4),
+    ];
+
+    #[test]
+    fn pad_grid_mapping() {
+        for (pad, x, y, label) in PAD_GRID {
+            assert_eq!(pad_to_xy(pad), (x, y), "pad {:X}", pad);
+            assert_eq!(xy_to_pad(x, y), Some(pad), "({}, {})", x, y);
+            assert_eq!((3 - y) * 4 + x + 1, label, "pad {:X}", pad);
+        }
+    }
+
+    #[test]
+    fn short_pad_reports_are_skipped() {
+        let mut buffer = [0u8; 40];
+        buffer[30] = 0xFF;
+        buffer[31] = 0xF1;
+
+        let values: Vec<(usize, u16)> = pad_report_values(&buffer).collect();
+        assert_eq!(values.len(), 16);
+        assert_eq!(values[15], (0xF, 0x1FF));
+
+        assert_eq!(pad_report_values(&buffer[..20]).count(), 0);
+        assert_eq!(pad_report_values(&[]).count(), 0);
+    }
+
+    #[test]
+    fn pad_pressure_normalized_from_threshold() {
+        assert_eq!(normalize_pad_pressure(0, 512), 0.0);
+        assert_eq!(normalize_pad_pressure(512, 512), 0.0);
+        assert_eq!(normalize_pad_pressure(0x0FFF, 512), 1.0);
+        assert_eq!(normalize_pad_pressure(0xFFFF, 512), 1.0);
+        assert!((normalize_pad_pressure(2304, 512) - 0.5).abs() < 0.001);
+        assert_eq!(normalize_pad_pressure(0x0FFF, 0x0FFF), 0.0);
+    }
+
+    #[test]
+    fn tick_tasks_follow_policy() {
+        let mut core = DeviceCore::new();
+        assert_eq!(core.next_tick(), &[TickTask::Frame]);
+        assert_eq!(core.next_tick(), &[TickTask::Leds]);
+        assert_eq!(core.next_tick(), &[TickTask::Read]);
+        assert_eq!(core.next_tick(), &[TickTask::Frame]);
+
+        core.set_tick_policy(TickPolicy::ReadEveryTick);
+        for _ in 0..3 {
+            assert_eq!(
+                core.next_tick(),
+                &[TickTask::Read, TickTask::Frame, TickTask::Leds]
+            );
+        }
+    }
+
+    #[test]
+    fn frame_rate_limit_skips_early_frames() {
+        assert_eq!(frame_interval(0), None);
+        assert_eq!(frame_interval(50), Some(Duration::from_millis(20)));
+
+        let now = Instant::now();
+        let mut due_at = None;
+        assert!(frame_due(frame_interval(0), &mut due_at, now));
+        assert!(frame_due(frame_interval(0), &mut due_at, now));
+        assert!(due_at.is_none());
+
+        let interval = frame_interval(50);
+        assert!(frame_due(interval, &mut due_at, now));
+        assert!(!frame_due(
+            interval,
+            &mut due_at,
+            now + Duration::from_millis(10)
+        ));
+        assert!(frame_due(
+            interval,
+            &mut due_at,
+            now + Duration::from_millis(20)
+        ));
+        assert_eq!(due_at, Some(now + Duration::from_millis(40)));
+    }
 
-pub use maschine_mikro_mk2::MaschineMikroMk2;
+    #[test]
+    fn xy_to_pad_out_of_range() {
+        assert_eq!(xy_to_pad(4, 0), None);
+        assert_eq!(xy_to_pad(0, 4), None);
+    }
+}
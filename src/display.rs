@@ -1,4 +1,7 @@
+use crate::colour::Colour;
 use crate::events::Direction;
+use crate::fonts::DEFAULT_FONT;
+use crate::image::Image;
 ///
 /// # Display interface
 ///
@@ -9,6 +12,11 @@ use std::cmp::{max, min};
 ///
 pub type Font = [(u8, [u8; 5]); 96];
 
+///
+/// Vertical offset of a glyph within an 8 pixel text row
+///
+pub(crate) const GLYPH_OFFSET: usize = 2;
+
 ///
 /// State of a pixel
 ///
@@ -18,9 +26,31 @@ pub enum Pixel {
     Off,
 }
 
+///
+/// Horizontal alignment of text
+///
+#[derive(Debug, Copy, Clone)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+///
+/// Direction of horizontal scrolling
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HorizontalDirection {
+    Left,
+    Right,
+}
+
 ///
 /// Basic display interface
 ///
+/// The trait is object safe (it has no generic methods) so canvases can be chosen at runtime,
+/// see `BoxedCanvas`.
+///
 pub trait Canvas<T: Clone> {
     ///
     /// Width of the display
@@ -52,6 +82,11 @@ pub trait Canvas<T: Clone> {
     ///
     fn clear_dirty_flag(&mut self);
 
+    ///
+    /// Set the data dirty flag, forcing the data to be sent again
+    ///
+    fn set_dirty_flag(&mut self);
+
     ///
     /// Invert all pixels
     ///
@@ -67,6 +102,11 @@ pub trait Canvas<T: Clone> {
     ///
     fn invert_row_slice(&mut self, row: usize, start_col: usize, end_col: usize);
 
+    ///
+    /// Invert a rectangle, clipped to the canvas
+    ///
+    fn invert_rect(&mut self, x: usize, y: usize, w: usize, h: usize);
+
     ///
     /// Fill the entire canvas with a single colour
     ///
@@ -78,7 +118,7 @@ pub trait Canvas<T: Clone> {
     fn fill_row(&mut self, row: usize, colour: T);
 
     /// Fill multiple rows with a single colour
-    fn fill_rows(&mut self, start_row: usize, end_row: usize, colour: Pixel);
+    fn fill_rows(&mut self, start_row: usize, end_row: usize, colour: T);
 
     ///
     /// Set a pixel
@@ -93,12 +133,152 @@ pub trait Canvas<T: Clone> {
     ///
     /// Copy canvas
     ///
+    /// Canvases of the same size are copied whole, otherwise only the region overlapping from
+    /// the top left corner is copied and the rest of this canvas is left unchanged.
+    ///
     fn copy_from(&mut self, canvas: &dyn Canvas<T>);
 
+    ///
+    /// Copy a region of another canvas onto this canvas at an offset
+    ///
+    /// The region is clipped to both the source and this canvas.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    fn copy_region(
+        &mut self,
+        src: &dyn Canvas<T>,
+        src_x: usize,
+        src_y: usize,
+        w: usize,
+        h: usize,
+        dst_x: usize,
+        dst_y: usize,
+    ) {
+        let w = min(
+            w,
+            min(
+                src.width().saturating_sub(src_x),
+                self.width().saturating_sub(dst_x),
+            ),
+        );
+        let h = min(
+            h,
+            min(
+                src.height().saturating_sub(src_y),
+                self.height().saturating_sub(dst_y),
+            ),
+        );
+        for y in 0..h {
+            for x in 0..w {
+                if let Some(colour) = src.pixel(src_x + x, src_y + y) {
+                    self.set_pixel(dst_x + x, dst_y + y, colour);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Draw a line between two points (Bresenham's algorithm)
+    ///
+    /// The line is clipped to the canvas before it is drawn.
+    ///
+    fn draw_line(&mut self, x0: usize, y0: usize, x1: usize, y1: usize, colour: T) {
+        let (width, height) = (self.width(), self.height());
+        if width == 0 || height == 0 {
+            return;
+        }
+        let (x0, y0, x1, y1) = match clip_line(x0, y0, x1, y1, width - 1, height - 1) {
+            Some(line) => line,
+            None => return,
+        };
+        let (mut x, mut y) = (x0 as isize, y0 as isize);
+        let (x1, y1) = (x1 as isize, y1 as isize);
+
+        let dx = (x1 - x).abs();
+        let dy = -(y1 - y).abs();
+        let sx = if x < x1 { 1 } else { -1 };
+        let sy = if y < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+
+        loop {
+            self.set_pixel(x as usize, y as usize, colour.clone());
+            if x == x1 && y == y1 {
+                break;
+            }
+
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    ///
+    /// Draw the outline of a rectangle
+    ///
+    /// Edges that fall outside of the canvas are clipped.
+    ///
+    fn draw_rect(&mut self, x: usize, y: usize, w: usize, h: usize, colour: T) {
+        if w == 0 || h == 0 {
+            return;
+        }
+
+        let (x1, y1) = (x.saturating_add(w - 1), y.saturating_add(h - 1));
+        self.draw_line(x, y, x1, y, colour.clone());
+        self.draw_line(x, y1, x1, y1, colour.clone());
+        self.draw_line(x, y, x, y1, colour.clone());
+        self.draw_line(x1, y, x1, y1, colour);
+    }
+
+    ///
+    /// Draw a filled rectangle
+    ///
+    /// The rectangle is clipped to the canvas.
+    ///
+    fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, colour: T) {
+        let x_end = min(x.saturating_add(w), self.width());
+        let y_end = min(y.saturating_add(h), self.height());
+        for py in y..y_end {
+            for px in x..x_end {
+                self.set_pixel(px, py, colour.clone());
+            }
+        }
+    }
+
+    ///
+    /// Draw a horizontal progress bar, an outline filled from the left by fraction (0..1)
+    ///
+    /// The unfilled part of the bar is left untouched, clear it first if the value can fall.
+    ///
+    fn draw_progress_bar(
+        &mut self,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+        fraction: f32,
+        colour: T,
+    ) {
+        self.draw_rect(x, y, w, h, colour.clone());
+        if w > 2 && h > 2 {
+            let inner = w - 2;
+            let filled = (fraction.clamp(0.0, 1.0) * inner as f32).round() as usize;
+            self.fill_rect(x + 1, y + 1, filled, h - 2, colour);
+        }
+    }
+
     ///
     /// Print, handles newlines but not scrolling
     ///
-    fn print(&mut self, s: &str, row: usize, col: usize, font: &Font, colour: T) {
+    /// Returns the (row, col) following the last character so further text can be printed
+    /// straight after it.
+    ///
+    fn print(&mut self, s: &str, row: usize, col: usize, font: &Font, colour: T) -> (usize, usize) {
         let mut row = row;
         let mut col = col;
         for c in s.chars() {
@@ -112,6 +292,98 @@ pub trait Canvas<T: Clone> {
                 }
             }
         }
+        (row, col)
+    }
+
+    ///
+    /// Print using the default font (`fonts::DEFAULT_FONT`), see `print`
+    ///
+    fn print_default(&mut self, s: &str, row: usize, col: usize, colour: T) -> (usize, usize) {
+        self.print(s, row, col, &DEFAULT_FONT, colour)
+    }
+
+    ///
+    /// Print with each line aligned across the width of the canvas
+    ///
+    /// Lines wider than the canvas are truncated to the characters that fit.
+    ///
+    fn print_aligned(&mut self, s: &str, row: usize, font: &Font, colour: T, align: Align) {
+        for (idx, line) in s.split('\n').enumerate() {
+            let line = fit_line(line, font, self.width());
+            let spare = self.width().saturating_sub(line_width(line, font));
+            let col = match align {
+                Align::Left => 0,
+                Align::Center => spare / 2,
+                Align::Right => spare,
+            };
+            self.print(line, row + idx, col, font, colour.clone());
+        }
+    }
+
+    ///
+    /// Print, wrapping words onto the next row when they would overrun the canvas
+    ///
+    /// Wrapped rows start at the same column as the first. Words wider than the available
+    /// width are split across rows. Drawing stops at the last row of the canvas, returns the
+    /// number of rows drawn.
+    ///
+    fn print_wrapped(&mut self, s: &str, row: usize, col: usize, font: &Font, colour: T) -> usize {
+        let limit = self.width();
+        let bands = self.height() / 8;
+        let space = glyph_width(' ', font);
+        if row >= bands {
+            return 0;
+        }
+        let mut row = row;
+        let mut rows = 1;
+
+        for (idx, line) in s.split('\n').enumerate() {
+            if idx > 0 {
+                row += 1;
+                if row >= bands {
+                    return rows;
+                }
+                rows += 1;
+            }
+
+            // End (exclusive) of the last glyph printed on the current row
+            let mut end: Option<usize> = None;
+            for word in line.split_whitespace() {
+                let width = line_width(word, font);
+                let mut pos = match end {
+                    Some(end) if end + space + 2 + width <= limit => end + space + 2,
+                    Some(_) => {
+                        row += 1;
+                        if row >= bands {
+                            return rows;
+                        }
+                        rows += 1;
+                        col
+                    }
+                    None => col,
+                };
+
+                for c in word.chars() {
+                    let glyph = glyph_width(c, font);
+                    if glyph == 0 {
+                        continue;
+                    }
+                    if pos + glyph > limit && pos > col {
+                        row += 1;
+                        if row >= bands {
+                            return rows;
+                        }
+                        rows += 1;
+                        pos = col;
+                    }
+                    pos += self.print_char(c, row, pos, font, colour.clone());
+                    end = Some(pos);
+                    pos += 1;
+                }
+            }
+        }
+
+        rows
     }
 
     ///
@@ -119,10 +391,224 @@ pub trait Canvas<T: Clone> {
     ///
     fn print_char(&mut self, t: char, row: usize, col: usize, font: &Font, colour: T) -> usize;
 
+    ///
+    /// Print character with its top left corner at a pixel position
+    ///
+    /// Only the pixels of the glyph are drawn, the background is left untouched. Returns the
+    /// width of the glyph.
+    ///
+    fn print_char_at(&mut self, c: char, x: usize, y: usize, font: &Font, colour: T) -> usize {
+        let width = glyph_width(c, font);
+        if width == 0 {
+            return 0;
+        }
+        let (_, glyph) = font[c as usize - 0x20];
+        for (slice, bits) in glyph.iter().take(width).enumerate() {
+            for bit in 0..8 {
+                if bits & (1 << bit) != 0 {
+                    self.set_pixel(x + slice, y + bit, colour.clone());
+                }
+            }
+        }
+        width
+    }
+
     ///
     /// Vertical scroll the rows in a particular direction
     ///
     fn vscroll_rows(&mut self, row_start: usize, row_end: usize, direction: Direction);
+
+    ///
+    /// Horizontal scroll the columns between start and end (exclusive) by one pixel
+    ///
+    /// If wrap is set the column that falls off one edge reappears on the other, otherwise
+    /// it is cleared.
+    ///
+    fn hscroll(
+        &mut self,
+        start_col: usize,
+        end_col: usize,
+        direction: HorizontalDirection,
+        wrap: bool,
+    );
+
+    ///
+    /// Mirror the canvas left to right
+    ///
+    fn flip_horizontal(&mut self) {
+        let (width, height) = (self.width(), self.height());
+        for y in 0..height {
+            for x in 0..(width / 2) {
+                let (left, right) = (self.pixel(x, y), self.pixel(width - x - 1, y));
+                if let (Some(left), Some(right)) = (left, right) {
+                    self.set_pixel(x, y, right);
+                    self.set_pixel(width - x - 1, y, left);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Mirror the canvas top to bottom
+    ///
+    fn flip_vertical(&mut self) {
+        let (width, height) = (self.width(), self.height());
+        for y in 0..(height / 2) {
+            for x in 0..width {
+                let (top, bottom) = (self.pixel(x, y), self.pixel(x, height - y - 1));
+                if let (Some(top), Some(bottom)) = (top, bottom) {
+                    self.set_pixel(x, y, bottom);
+                    self.set_pixel(x, height - y - 1, top);
+                }
+            }
+        }
+    }
+
+    ///
+    /// Rotate the canvas through 180 degrees (eg for a controller mounted upside down)
+    ///
+    fn rotate_180(&mut self) {
+        self.flip_horizontal();
+        self.flip_vertical();
+    }
+}
+
+///
+/// Canvas chosen at runtime, eg drawing to the device display or an off screen canvas
+///
+/// Every `Canvas` method is available on the boxed canvas and it can be passed anywhere a
+/// `&dyn Canvas<T>` is expected (eg `copy_from`).
+///
+/// ```
+/// use maschine::{BoxedCanvas, Canvas, MonochromeCanvas, Pixel};
+///
+/// fn draw(canvas: &mut dyn Canvas<Pixel>) {
+///     canvas.fill_rect(0, 0, 8, 8, Pixel::On);
+/// }
+///
+/// let mut canvas: BoxedCanvas<Pixel> = Box::new(MonochromeCanvas::new(128, 64));
+/// draw(canvas.as_mut());
+///
+/// let mut display = MonochromeCanvas::new(128, 64);
+/// display.copy_from(canvas.as_ref());
+/// assert_eq!(display.data()[0], 0xFF);
+/// ```
+///
+pub type BoxedCanvas<T> = Box<dyn Canvas<T>>;
+
+///
+/// Width of a glyph, non-printable characters have no width
+///
+pub(crate) fn glyph_width(c: char, font: &Font) -> usize {
+    let raw = c as usize;
+    if (0x20..=0x7F).contains(&raw) {
+        font[raw - 0x20].0 as usize
+    } else {
+        0
+    }
+}
+
+///
+/// Rendered width of text in pixels including the single pixel of spacing between characters
+///
+/// Non-printable characters have no width, for multi-line text the widest line is returned.
+///
+pub fn text_width(s: &str, font: &Font) -> usize {
+    s.split('\n')
+        .map(|line| line_width(line, font))
+        .max()
+        .unwrap_or(0)
+}
+
+///
+/// Rendered width of a single line of text including the spacing between characters
+///
+fn line_width(s: &str, font: &Font) -> usize {
+    let widths: Vec<usize> = s
+        .chars()
+        .map(|c| glyph_width(c, font))
+        .filter(|w| *w > 0)
+        .collect();
+    widths.iter().sum::<usize>() + widths.len().saturating_sub(1)
+}
+
+///
+/// Clip a line to the rectangle from the origin to (max_x, max_y) inclusive (Liang-Barsky)
+///
+/// Returns None if no part of the line is within the rectangle.
+///
+fn clip_line(
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    max_x: usize,
+    max_y: usize,
+) -> Option<(usize, usize, usize, usize)> {
+    if max(x0, x1) <= max_x && max(y0, y1) <= max_y {
+        return Some((x0, y0, x1, y1));
+    }
+
+    let (fx, fy) = (x0 as f64, y0 as f64);
+    let (dx, dy) = (x1 as f64 - fx, y1 as f64 - fy);
+    let (mut enter, mut exit) = (0f64, 1f64);
+    for (p, q) in [
+        (-dx, fx),
+        (dx, max_x as f64 - fx),
+        (-dy, fy),
+        (dy, max_y as f64 - fy),
+    ] {
+        if p == 0.0 {
+            // Parallel to this edge, entirely outside if beyond it
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            enter = enter.max(q / p);
+        } else {
+            exit = exit.min(q / p);
+        }
+    }
+    if enter > exit {
+        return None;
+    }
+
+    let point = |t: f64| {
+        (
+            min((fx + t * dx).round() as usize, max_x),
+            min((fy + t * dy).round() as usize, max_y),
+        )
+    };
+    let ((x0, y0), (x1, y1)) = (point(enter), point(exit));
+    Some((x0, y0, x1, y1))
+}
+
+///
+/// Longest prefix of a line of text that fits within a width
+///
+fn fit_line<'a>(s: &'a str, font: &Font, limit: usize) -> &'a str {
+    let mut width = 0;
+    for (idx, c) in s.char_indices() {
+        let glyph = glyph_width(c, font);
+        if glyph == 0 {
+            continue;
+        }
+        let end = if width == 0 { glyph } else { width + 1 + glyph };
+        if end > limit {
+            return &s[..idx];
+        }
+        width = end;
+    }
+    s
+}
+
+///
+/// Mask of the bits within a row band covered by the pixel rows y_start..y_end
+///
+fn band_mask(row: usize, y_start: usize, y_end: usize) -> u8 {
+    let top = max(y_start, row << 3) & 7;
+    let bottom = min(y_end, (row + 1) << 3) - (row << 3);
+    ((0xFFu16 << top) & (0xFFu16 >> (8 - bottom))) as u8
 }
 
 ///
@@ -134,7 +620,7 @@ pub struct MonochromeCanvas {
     width: usize,
     height: usize,
     buffer: Vec<u8>,
-    dirty: bool,
+    dirty_rows: Vec<bool>,
 }
 
 impl MonochromeCanvas {
@@ -143,7 +629,7 @@ impl MonochromeCanvas {
             width,
             height,
             buffer: vec![0; (width * height) / 8],
-            dirty: true,
+            dirty_rows: vec![true; height >> 3],
         }
     }
 
@@ -157,8 +643,110 @@ impl MonochromeCanvas {
             width,
             height,
             buffer: buffer.to_vec(),
-            dirty: true,
+            dirty_rows: vec![true; height >> 3],
+        }
+    }
+
+    ///
+    /// Rows (8 pixels) that have changed since the dirty flag was last cleared
+    ///
+    pub fn dirty_rows(&self) -> impl Iterator<Item = usize> + '_ {
+        self.dirty_rows
+            .iter()
+            .enumerate()
+            .filter(|(_, dirty)| **dirty)
+            .map(|(row, _)| row)
+    }
+
+    ///
+    /// Determine if a row (8 pixels) has changed since the dirty flag was last cleared
+    ///
+    pub fn is_row_dirty(&self, row: usize) -> bool {
+        self.dirty_rows.get(row).copied().unwrap_or(false)
+    }
+
+    ///
+    /// Mark rows between start and end (exclusive) as changed
+    ///
+    fn mark_rows_dirty(&mut self, start_row: usize, end_row: usize) {
+        let end_row = min(end_row, self.dirty_rows.len());
+        for dirty in self.dirty_rows[min(start_row, end_row)..end_row].iter_mut() {
+            *dirty = true;
+        }
+    }
+
+    ///
+    /// Draw an image onto the canvas with the top-left corner at x, y
+    ///
+    /// Pixels with a luminance below the threshold are set On (in the same way dark pixels
+    /// are used for font glyphs), the rest are set Off. Anything that falls outside of the
+    /// canvas is clipped.
+    ///
+    pub fn blit_bitmap<I: Image>(&mut self, bmp: &I, x: usize, y: usize, threshold: u8) {
+        let x_end = min(x.saturating_add(bmp.width()), self.width);
+        let y_end = min(y.saturating_add(bmp.height()), self.height);
+        for py in y..y_end {
+            for px in x..x_end {
+                let colour = if bmp.pixel(px - x, py - y).luminance() < threshold {
+                    Pixel::On
+                } else {
+                    Pixel::Off
+                };
+                self.set_pixel(px, py, colour);
+            }
+        }
+    }
+
+    ///
+    /// Fill the canvas with a checkerboard of squares `size` pixels across, starting with an
+    /// On square in the top-left corner
+    ///
+    pub fn fill_checkerboard(&mut self, size: usize) {
+        let size = max(size, 1);
+        for row in 0..self.dirty_rows.len() {
+            // Columns alternate between a band pattern and its inverse
+            let band = (0..8)
+                .filter(|bit| (((row << 3) + bit) / size) & 1 == 0)
+                .fold(0u8, |band, bit| band | (1 << bit));
+
+            let start = row * self.width;
+            for (x, byte) in self.buffer[start..start + self.width]
+                .iter_mut()
+                .enumerate()
+            {
+                *byte = if (x / size) & 1 == 0 { band } else { !band };
+            }
+        }
+        self.set_dirty_flag();
+    }
+
+    ///
+    /// Fill every column of each 8 pixel row band with a bit pattern, the least significant
+    /// bit is the top pixel of the band (eg 0x55 draws alternate lines)
+    ///
+    pub fn fill_pattern(&mut self, pattern: u8) {
+        self.buffer.fill(pattern);
+        self.set_dirty_flag();
+    }
+
+    ///
+    /// Render the canvas as text for previewing in a terminal
+    ///
+    /// Each line is a row of pixels, On pixels are drawn as a full block and Off pixels as a
+    /// space.
+    ///
+    pub fn to_ascii(&self) -> String {
+        let mut output = String::with_capacity((self.width + 1) * self.height * 3);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                output.push(match self.pixel(x, y) {
+                    Some(Pixel::On) => '\u{2588}',
+                    _ => ' ',
+                });
+            }
+            output.push('\n');
         }
+        output
     }
 }
 
@@ -180,18 +768,22 @@ impl Canvas<Pixel> for MonochromeCanvas {
     }
 
     fn is_dirty(&self) -> bool {
-        self.dirty
+        self.dirty_rows.iter().any(|dirty| *dirty)
     }
 
     fn clear_dirty_flag(&mut self) {
-        self.dirty = false;
+        self.dirty_rows.fill(false);
+    }
+
+    fn set_dirty_flag(&mut self) {
+        self.dirty_rows.fill(true);
     }
 
     fn invert(&mut self) {
         for byte in self.buffer.iter_mut() {
             *byte = !(*byte);
         }
-        self.dirty = true;
+        self.set_dirty_flag();
     }
 
     ///
@@ -203,6 +795,7 @@ impl Canvas<Pixel> for MonochromeCanvas {
         for byte in self.buffer[start..end].iter_mut() {
             *byte = !*byte;
         }
+        self.mark_rows_dirty(row, row + 1);
     }
 
     ///
@@ -214,6 +807,28 @@ impl Canvas<Pixel> for MonochromeCanvas {
         for byte in self.buffer[start..end].iter_mut() {
             *byte = !*byte;
         }
+        self.mark_rows_dirty(row, row + 1);
+    }
+
+    ///
+    /// Invert a rectangle, toggling only the bits of each row band it covers
+    ///
+    fn invert_rect(&mut self, x: usize, y: usize, w: usize, h: usize) {
+        let x_end = min(x.saturating_add(w), self.width);
+        let y_end = min(y.saturating_add(h), self.height);
+        if x >= x_end || y >= y_end {
+            return;
+        }
+
+        for row in (y >> 3)..=((y_end - 1) >> 3) {
+            let mask = band_mask(row, y, y_end);
+            let start = row * self.width;
+            for byte in self.buffer[(start + x)..(start + x_end)].iter_mut() {
+                *byte ^= mask;
+            }
+        }
+
+        self.mark_rows_dirty(y >> 3, ((y_end - 1) >> 3) + 1);
     }
 
     ///
@@ -229,7 +844,7 @@ impl Canvas<Pixel> for MonochromeCanvas {
             *byte = value;
         }
 
-        self.dirty = true;
+        self.set_dirty_flag();
     }
 
     ///
@@ -247,11 +862,11 @@ impl Canvas<Pixel> for MonochromeCanvas {
             *byte = value;
         }
 
-        self.dirty = true;
+        self.mark_rows_dirty(row, row + 1);
     }
 
     ///
-    /// Fill the entire canvas with a single colour
+    /// Fill multiple rows with a single colour, rows beyond the canvas are ignored
     ///
     fn fill_rows(&mut self, start_row: usize, end_row: usize, colour: Pixel) {
         let value = match colour {
@@ -259,13 +874,19 @@ impl Canvas<Pixel> for MonochromeCanvas {
             Pixel::Off => 0x00u8,
         };
 
+        let bands = self.height >> 3;
+        let (start_row, end_row) = (min(start_row, bands), min(end_row, bands));
+        if start_row >= end_row {
+            return;
+        }
+
         let start = start_row * self.width;
         let end = end_row * self.width;
         for byte in self.buffer[start..end].iter_mut() {
             *byte = value;
         }
 
-        self.dirty = true;
+        self.mark_rows_dirty(start_row, end_row);
     }
 
     ///
@@ -274,7 +895,7 @@ impl Canvas<Pixel> for MonochromeCanvas {
     fn set_pixel(&mut self, x: usize, y: usize, colour: Pixel) {
         let width = self.width();
         let height = self.height();
-        if (x > width) | (y > height) {
+        if (x >= width) | (y >= height) {
             return;
         }
 
@@ -284,19 +905,19 @@ impl Canvas<Pixel> for MonochromeCanvas {
             Pixel::Off => self.buffer[byte_index] &= !(1 << (y & 7)),
         }
 
-        self.dirty = true;
+        self.dirty_rows[y >> 3] = true;
     }
 
     ///
     /// Get state of a pixel
     ///
     fn pixel(&self, x: usize, y: usize) -> Option<Pixel> {
-        if (x > self.width) | (y > self.height) {
+        if (x >= self.width) | (y >= self.height) {
             return None;
         }
 
         let byte_index = (self.width * (y >> 3)) + x;
-        let pixel = self.buffer[byte_index] >> ((y & 7) & 0x01);
+        let pixel = (self.buffer[byte_index] >> (y & 7)) & 0x01;
         Some(if pixel == 0 { Pixel::Off } else { Pixel::On })
     }
 
@@ -304,7 +925,36 @@ impl Canvas<Pixel> for MonochromeCanvas {
     /// Copy canvas
     ///
     fn copy_from(&mut self, canvas: &dyn Canvas<Pixel>) {
-        self.buffer = canvas.data().to_vec();
+        if (canvas.width(), canvas.height()) == (self.width, self.height) {
+            self.buffer.copy_from_slice(canvas.data());
+            self.set_dirty_flag();
+        } else {
+            self.copy_region(canvas, 0, 0, canvas.width(), canvas.height(), 0, 0);
+        }
+    }
+
+    ///
+    /// Draw a filled rectangle, writing a whole row band byte at a time
+    ///
+    fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, colour: Pixel) {
+        let x_end = min(x.saturating_add(w), self.width);
+        let y_end = min(y.saturating_add(h), self.height);
+        if x >= x_end || y >= y_end {
+            return;
+        }
+
+        for row in (y >> 3)..=((y_end - 1) >> 3) {
+            let mask = band_mask(row, y, y_end);
+            let start = row * self.width;
+            for byte in self.buffer[(start + x)..(start + x_end)].iter_mut() {
+                match colour {
+                    Pixel::On => *byte |= mask,
+                    Pixel::Off => *byte &= !mask,
+                }
+            }
+        }
+
+        self.mark_rows_dirty(y >> 3, ((y_end - 1) >> 3) + 1);
     }
 
     ///
@@ -317,26 +967,82 @@ impl Canvas<Pixel> for MonochromeCanvas {
         }
         let char_idx = raw - 0x20;
         let (width, glyph) = font[char_idx];
-        for slice in 0..(width as usize) {
-            self.buffer[(row * self.width) + col + slice] = match colour {
-                Pixel::On => glyph[slice] << 2,
-                Pixel::Off => !(glyph[slice] << 2),
+        if row >= self.dirty_rows.len() {
+            return width as usize;
+        }
+
+        // Columns beyond the edge of the canvas are clipped
+        let start = row * self.width;
+        let columns = min(width as usize, self.width.saturating_sub(col));
+        for (slice, bits) in glyph.iter().take(columns).enumerate() {
+            self.buffer[start + col + slice] = match colour {
+                Pixel::On => bits << GLYPH_OFFSET,
+                Pixel::Off => !(bits << GLYPH_OFFSET),
             }
         }
-        self.dirty = true;
+        self.mark_rows_dirty(row, row + 1);
         width as usize
     }
 
     ///
-    /// Vertical scroll the rows in a particular direction
+    /// Print single character at a pixel position, glyph bits are merged into the one or two
+    /// row bands the glyph overlaps
     ///
-    fn vscroll_rows(&mut self, row_start: usize, row_end: usize, direction: Direction) {
-        let start = min(row_start, row_end) * self.width;
-        let end = max(row_start, row_end) * self.width;
-        match direction {
-            Direction::Up => {
-                for row in (start..end).rev() {
-                    self.buffer[row + self.width] = self.buffer[row];
+    fn print_char_at(&mut self, c: char, x: usize, y: usize, font: &Font, colour: Pixel) -> usize {
+        let width = glyph_width(c, font);
+        if width == 0 {
+            return 0;
+        }
+        if y >= self.height {
+            return width;
+        }
+
+        let (_, glyph) = font[c as usize - 0x20];
+        let band = y >> 3;
+        let bands = self.height >> 3;
+        let mut end_band = band + 1;
+        for (slice, bits) in glyph.iter().take(width).enumerate() {
+            let col = x + slice;
+            if col >= self.width {
+                break;
+            }
+
+            let bits = (*bits as u16) << (y & 7);
+            for (idx, mask) in [(band, bits as u8), (band + 1, (bits >> 8) as u8)] {
+                if idx >= bands || mask == 0 {
+                    continue;
+                }
+                let byte = &mut self.buffer[(idx * self.width) + col];
+                match colour {
+                    Pixel::On => *byte |= mask,
+                    Pixel::Off => *byte &= !mask,
+                }
+                end_band = max(end_band, idx + 1);
+            }
+        }
+
+        self.mark_rows_dirty(band, end_band);
+        width
+    }
+
+    ///
+    /// Vertical scroll the rows in a particular direction
+    ///
+    fn vscroll_rows(&mut self, row_start: usize, row_end: usize, direction: Direction) {
+        // Rows are scrolled through the last row, so clamp to the last row of the canvas
+        let last_row = (self.height >> 3).saturating_sub(1);
+        if min(row_start, row_end) > last_row || self.height < 8 {
+            return;
+        }
+        let row_start = min(row_start, last_row);
+        let row_end = min(row_end, last_row);
+
+        let start = min(row_start, row_end) * self.width;
+        let end = max(row_start, row_end) * self.width;
+        match direction {
+            Direction::Up => {
+                for row in (start..end).rev() {
+                    self.buffer[row + self.width] = self.buffer[row];
                 }
                 for row in start..(start + self.width) {
                     self.buffer[row] = 0;
@@ -351,6 +1057,1375 @@ impl Canvas<Pixel> for MonochromeCanvas {
                 }
             }
         }
+        self.mark_rows_dirty(min(row_start, row_end), max(row_start, row_end) + 1);
+    }
+
+    ///
+    /// Horizontal scroll the columns in a particular direction
+    ///
+    fn hscroll(
+        &mut self,
+        start_col: usize,
+        end_col: usize,
+        direction: HorizontalDirection,
+        wrap: bool,
+    ) {
+        let start = min(start_col, end_col);
+        let end = min(max(start_col, end_col), self.width);
+        if start >= end {
+            return;
+        }
+
+        // Each row band is a run of columns, so the same shift is applied to every band
+        for band in 0..(self.height >> 3) {
+            let offset = band * self.width;
+            let columns = &mut self.buffer[(offset + start)..(offset + end)];
+            match direction {
+                HorizontalDirection::Left => {
+                    columns.rotate_left(1);
+                    if !wrap {
+                        columns[columns.len() - 1] = 0;
+                    }
+                }
+                HorizontalDirection::Right => {
+                    columns.rotate_right(1);
+                    if !wrap {
+                        columns[0] = 0;
+                    }
+                }
+            }
+        }
+        self.set_dirty_flag();
+    }
+
+    ///
+    /// Mirror the canvas left to right, reversing the columns of each row band
+    ///
+    fn flip_horizontal(&mut self) {
+        for band in self.buffer.chunks_exact_mut(self.width) {
+            band.reverse();
+        }
+        self.set_dirty_flag();
+    }
+
+    ///
+    /// Mirror the canvas top to bottom, reversing the row bands and the bits within each
+    ///
+    fn flip_vertical(&mut self) {
+        let bands = self.height >> 3;
+        for band in 0..(bands / 2) {
+            let (top, bottom) = self.buffer.split_at_mut((bands - band - 1) * self.width);
+            top[(band * self.width)..((band + 1) * self.width)]
+                .swap_with_slice(&mut bottom[..self.width]);
+        }
+        for byte in self.buffer.iter_mut() {
+            *byte = byte.reverse_bits();
+        }
+        self.set_dirty_flag();
+    }
+
+    ///
+    /// Rotate the canvas through 180 degrees, the last column of the last band becomes the
+    /// first and the bits in each band are reversed
+    ///
+    fn rotate_180(&mut self) {
+        self.buffer.reverse();
+        for byte in self.buffer.iter_mut() {
+            *byte = byte.reverse_bits();
+        }
+        self.set_dirty_flag();
+    }
+}
+
+///
+/// Colour display that uses packed 24bpp RGB for data display.
+///
+/// Rows are 8 pixels high to match the monochrome canvas, so text and scrolling behave the
+/// same on either canvas.
+///
+pub struct RgbCanvas {
+    width: usize,
+    height: usize,
+    buffer: Vec<u8>,
+    dirty: bool,
+}
+
+impl RgbCanvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        RgbCanvas {
+            width,
+            height,
+            buffer: vec![0; width * height * 3],
+            dirty: true,
+        }
+    }
+
+    /// Offset of a pixel in the buffer
+    fn offset(&self, x: usize, y: usize) -> usize {
+        ((y * self.width) + x) * 3
+    }
+
+    /// Range of buffer data covering a row (8 pixels), clipped to the canvas
+    fn row_range(&self, start_row: usize, end_row: usize) -> std::ops::Range<usize> {
+        let stride = self.width * 3;
+        min(start_row * 8, self.height) * stride..min(end_row * 8, self.height) * stride
+    }
+}
+
+impl Canvas<Colour> for RgbCanvas {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn data_size(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn data(&self) -> &[u8] {
+        self.buffer.as_slice()
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn clear_dirty_flag(&mut self) {
+        self.dirty = false;
+    }
+
+    fn set_dirty_flag(&mut self) {
+        self.dirty = true;
+    }
+
+    fn invert(&mut self) {
+        for byte in self.buffer.iter_mut() {
+            *byte = !(*byte);
+        }
+        self.dirty = true;
+    }
+
+    fn invert_row(&mut self, row: usize) {
+        self.invert_row_slice(row, 0, self.width);
+    }
+
+    fn invert_row_slice(&mut self, row: usize, start_col: usize, end_col: usize) {
+        let end_col = min(end_col, self.width);
+        if start_col >= end_col {
+            return;
+        }
+
+        for y in (row * 8)..min((row + 1) * 8, self.height) {
+            let start = self.offset(start_col, y);
+            let end = self.offset(end_col, y);
+            for byte in self.buffer[start..end].iter_mut() {
+                *byte = !*byte;
+            }
+        }
+        self.dirty = true;
+    }
+
+    fn invert_rect(&mut self, x: usize, y: usize, w: usize, h: usize) {
+        let x_end = min(x.saturating_add(w), self.width);
+        let y_end = min(y.saturating_add(h), self.height);
+        if x >= x_end || y >= y_end {
+            return;
+        }
+
+        for py in y..y_end {
+            let (start, end) = (self.offset(x, py), self.offset(x_end, py));
+            for byte in self.buffer[start..end].iter_mut() {
+                *byte = !*byte;
+            }
+        }
+        self.dirty = true;
+    }
+
+    fn fill(&mut self, colour: Colour) {
+        self.fill_rows(0, self.height.div_ceil(8), colour);
+    }
+
+    fn fill_row(&mut self, row: usize, colour: Colour) {
+        self.fill_rows(row, row + 1, colour);
+    }
+
+    fn fill_rows(&mut self, start_row: usize, end_row: usize, colour: Colour) {
+        let (r, g, b) = colour.components();
+        let range = self.row_range(start_row, max(start_row, end_row));
+        for pixel in self.buffer[range].chunks_exact_mut(3) {
+            pixel.copy_from_slice(&[r, g, b]);
+        }
+        self.dirty = true;
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize, colour: Colour) {
+        if (x >= self.width) | (y >= self.height) {
+            return;
+        }
+
+        let (r, g, b) = colour.components();
+        let offset = self.offset(x, y);
+        self.buffer[offset..(offset + 3)].copy_from_slice(&[r, g, b]);
+        self.dirty = true;
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> Option<Colour> {
+        if (x >= self.width) | (y >= self.height) {
+            return None;
+        }
+
+        let offset = self.offset(x, y);
+        Some(Colour::new(
+            self.buffer[offset],
+            self.buffer[offset + 1],
+            self.buffer[offset + 2],
+        ))
+    }
+
+    fn copy_from(&mut self, canvas: &dyn Canvas<Colour>) {
+        if (canvas.width(), canvas.height()) == (self.width, self.height) {
+            self.buffer.copy_from_slice(canvas.data());
+            self.set_dirty_flag();
+        } else {
+            self.copy_region(canvas, 0, 0, canvas.width(), canvas.height(), 0, 0);
+        }
+    }
+
+    ///
+    /// Print single character, the glyph is drawn in the colour on a black background
+    ///
+    fn print_char(
+        &mut self,
+        c: char,
+        row: usize,
+        col: usize,
+        font: &Font,
+        colour: Colour,
+    ) -> usize {
+        let width = glyph_width(c, font);
+        if width == 0 {
+            return 0;
+        }
+        let glyph = font[c as usize - 0x20].1;
+        for (slice, bits) in glyph.iter().take(width).enumerate() {
+            let bits = bits << GLYPH_OFFSET;
+            for bit in 0..8 {
+                let pixel = if bits & (1 << bit) != 0 {
+                    colour
+                } else {
+                    Colour::BLACK
+                };
+                self.set_pixel(col + slice, (row * 8) + bit, pixel);
+            }
+        }
+        width
+    }
+
+    ///
+    /// Vertical scroll the rows in a particular direction
+    ///
+    fn vscroll_rows(&mut self, row_start: usize, row_end: usize, direction: Direction) {
+        let band = self.row_range(0, 1).end;
+        let last_row = (self.buffer.len() / max(band, 1)).saturating_sub(1);
+        let start = self
+            .row_range(min(min(row_start, row_end), last_row), 0)
+            .start;
+        let end = self
+            .row_range(min(max(row_start, row_end), last_row), 0)
+            .start;
+        if start >= end || end + band > self.buffer.len() {
+            return;
+        }
+
+        // Matches the monochrome canvas, the range plus the following row is scrolled
+        let region = &mut self.buffer[start..(end + band)];
+        match direction {
+            Direction::Up => {
+                region.rotate_right(band);
+                region[..band].fill(0);
+            }
+            Direction::Down => {
+                region.rotate_left(band);
+                let len = region.len();
+                region[(len - band)..].fill(0);
+            }
+        }
+        self.dirty = true;
+    }
+
+    ///
+    /// Horizontal scroll the columns in a particular direction
+    ///
+    fn hscroll(
+        &mut self,
+        start_col: usize,
+        end_col: usize,
+        direction: HorizontalDirection,
+        wrap: bool,
+    ) {
+        let start = min(start_col, end_col);
+        let end = min(max(start_col, end_col), self.width);
+        if start >= end {
+            return;
+        }
+
+        for y in 0..self.height {
+            let (from, to) = (self.offset(start, y), self.offset(end, y));
+            let columns = &mut self.buffer[from..to];
+            match direction {
+                HorizontalDirection::Left => {
+                    columns.rotate_left(3);
+                    if !wrap {
+                        let len = columns.len();
+                        columns[(len - 3)..].fill(0);
+                    }
+                }
+                HorizontalDirection::Right => {
+                    columns.rotate_right(3);
+                    if !wrap {
+                        columns[..3].fill(0);
+                    }
+                }
+            }
+        }
         self.dirty = true;
     }
 }
+
+///
+/// Pair of canvases where drawing goes to a back buffer that is only made visible when
+/// `present` is called, so a complete frame can be rendered before any of it is displayed.
+///
+/// Data and the dirty flag come from the front buffer, drawing and pixel reads use the back
+/// buffer. Copy the presented frame onto a device display with `copy_from`.
+///
+pub struct DoubleBuffered<C> {
+    front: C,
+    back: C,
+}
+
+impl<C> DoubleBuffered<C> {
+    pub fn new(front: C, back: C) -> Self {
+        DoubleBuffered { front, back }
+    }
+
+    ///
+    /// Committed buffer
+    ///
+    pub fn front(&self) -> &C {
+        &self.front
+    }
+
+    ///
+    /// Buffer being drawn
+    ///
+    pub fn back(&mut self) -> &mut C {
+        &mut self.back
+    }
+
+    ///
+    /// Swap the buffers, making the frame drawn so far visible
+    ///
+    /// The new back buffer holds the previously presented frame.
+    ///
+    pub fn present<T: Clone>(&mut self)
+    where
+        C: Canvas<T>,
+    {
+        std::mem::swap(&mut self.front, &mut self.back);
+        self.front.set_dirty_flag();
+    }
+}
+
+impl DoubleBuffered<MonochromeCanvas> {
+    pub fn monochrome(width: usize, height: usize) -> Self {
+        DoubleBuffered::new(
+            MonochromeCanvas::new(width, height),
+            MonochromeCanvas::new(width, height),
+        )
+    }
+}
+
+impl<T: Clone, C: Canvas<T>> Canvas<T> for DoubleBuffered<C> {
+    fn width(&self) -> usize {
+        self.back.width()
+    }
+
+    fn height(&self) -> usize {
+        self.back.height()
+    }
+
+    fn data_size(&self) -> usize {
+        self.front.data_size()
+    }
+
+    fn data(&self) -> &[u8] {
+        self.front.data()
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.front.is_dirty()
+    }
+
+    fn clear_dirty_flag(&mut self) {
+        self.front.clear_dirty_flag();
+    }
+
+    fn set_dirty_flag(&mut self) {
+        self.front.set_dirty_flag();
+    }
+
+    fn invert(&mut self) {
+        self.back.invert();
+    }
+
+    fn invert_row(&mut self, row: usize) {
+        self.back.invert_row(row);
+    }
+
+    fn invert_row_slice(&mut self, row: usize, start_col: usize, end_col: usize) {
+        self.back.invert_row_slice(row, start_col, end_col);
+    }
+
+    fn invert_rect(&mut self, x: usize, y: usize, w: usize, h: usize) {
+        self.back.invert_rect(x, y, w, h);
+    }
+
+    fn fill(&mut self, colour: T) {
+        self.back.fill(colour);
+    }
+
+    fn fill_row(&mut self, row: usize, colour: T) {
+        self.back.fill_row(row, colour);
+    }
+
+    fn fill_rows(&mut self, start_row: usize, end_row: usize, colour: T) {
+        self.back.fill_rows(start_row, end_row, colour);
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize, colour: T) {
+        self.back.set_pixel(x, y, colour);
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> Option<T> {
+        self.back.pixel(x, y)
+    }
+
+    fn copy_from(&mut self, canvas: &dyn Canvas<T>) {
+        self.back.copy_from(canvas);
+    }
+
+    fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, colour: T) {
+        self.back.fill_rect(x, y, w, h, colour);
+    }
+
+    fn print_char(&mut self, c: char, row: usize, col: usize, font: &Font, colour: T) -> usize {
+        self.back.print_char(c, row, col, font, colour)
+    }
+
+    fn print_char_at(&mut self, c: char, x: usize, y: usize, font: &Font, colour: T) -> usize {
+        self.back.print_char_at(c, x, y, font, colour)
+    }
+
+    fn vscroll_rows(&mut self, row_start: usize, row_end: usize, direction: Direction) {
+        self.back.vscroll_rows(row_start, row_end, direction);
+    }
+
+    fn hscroll(
+        &mut self,
+        start_col: usize,
+        end_col: usize,
+        direction: HorizontalDirection,
+        wrap: bool,
+    ) {
+        self.back.hscroll(start_col, end_col, direction, wrap);
+    }
+
+    fn flip_horizontal(&mut self) {
+        self.back.flip_horizontal();
+    }
+
+    fn flip_vertical(&mut self) {
+        self.back.flip_vertical();
+    }
+
+    fn rotate_180(&mut self) {
+        self.back.rotate_180();
+    }
+}
+
+///
+/// Clipped region of another canvas.
+///
+/// Coordinates are relative to the top left of the viewport and anything drawn outside of it
+/// is dropped, so independent panels can be laid out on a single display.
+///
+pub struct Viewport<'a, C: ?Sized> {
+    canvas: &'a mut C,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, C: ?Sized> Viewport<'a, C> {
+    ///
+    /// Create a viewport onto a canvas, the rect is truncated to the canvas bounds
+    ///
+    pub fn new<T: Clone>(canvas: &'a mut C, x: usize, y: usize, width: usize, height: usize) -> Self
+    where
+        C: Canvas<T>,
+    {
+        let width = min(width, canvas.width().saturating_sub(x));
+        let height = min(height, canvas.height().saturating_sub(y));
+        Viewport {
+            canvas,
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    ///
+    /// Set a pixel, ignored if outside of the viewport
+    ///
+    pub fn set_pixel<T: Clone>(&mut self, x: usize, y: usize, colour: T)
+    where
+        C: Canvas<T>,
+    {
+        if x < self.width && y < self.height {
+            self.canvas.set_pixel(self.x + x, self.y + y, colour);
+        }
+    }
+
+    ///
+    /// Get a pixel, None if outside of the viewport
+    ///
+    pub fn pixel<T: Clone>(&self, x: usize, y: usize) -> Option<T>
+    where
+        C: Canvas<T>,
+    {
+        if x < self.width && y < self.height {
+            self.canvas.pixel(self.x + x, self.y + y)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Fill the entire viewport
+    ///
+    pub fn fill<T: Clone>(&mut self, colour: T)
+    where
+        C: Canvas<T>,
+    {
+        self.canvas
+            .fill_rect(self.x, self.y, self.width, self.height, colour);
+    }
+
+    ///
+    /// Fill a rectangle clipped to the viewport
+    ///
+    pub fn fill_rect<T: Clone>(&mut self, x: usize, y: usize, w: usize, h: usize, colour: T)
+    where
+        C: Canvas<T>,
+    {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let w = min(w, self.width - x);
+        let h = min(h, self.height - y);
+        self.canvas.fill_rect(self.x + x, self.y + y, w, h, colour);
+    }
+
+    ///
+    /// Print text, rows are 8 pixels high from the top of the viewport
+    ///
+    /// Only the pixels of each glyph are drawn, the background is left untouched. Returns the
+    /// (row, col) following the last character.
+    ///
+    pub fn print<T: Clone>(
+        &mut self,
+        s: &str,
+        row: usize,
+        col: usize,
+        font: &Font,
+        colour: T,
+    ) -> (usize, usize)
+    where
+        C: Canvas<T>,
+    {
+        let mut row = row;
+        let mut col = col;
+        for c in s.chars() {
+            if c == '\n' {
+                row += 1;
+                col = 0;
+                continue;
+            }
+            let width = glyph_width(c, font);
+            if width == 0 {
+                continue;
+            }
+            let (_, glyph) = font[c as usize - 0x20];
+            for (slice, bits) in glyph.iter().take(width).enumerate() {
+                for bit in 0..8 {
+                    if bits & (1 << bit) != 0 {
+                        let y = (row * 8) + GLYPH_OFFSET + bit;
+                        self.set_pixel(col + slice, y, colour.clone());
+                    }
+                }
+            }
+            col += width + 1;
+        }
+        (row, col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_line_horizontal() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.draw_line(0, 0, 9, 0, Pixel::On);
+
+        assert!(canvas.data()[0..10].iter().all(|b| *b == 0x01));
+        assert_eq!(canvas.data()[10], 0x00);
+    }
+
+    #[test]
+    fn draw_line_steep_diagonal() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.draw_line(0, 7, 7, 0, Pixel::On);
+
+        for x in 0..8 {
+            assert_eq!(canvas.data()[x], 1 << (7 - x));
+        }
+    }
+
+    #[test]
+    fn draw_line_is_clipped() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.draw_line(120, 0, 200, 0, Pixel::On);
+        canvas.draw_line(0, 60, 0, 100, Pixel::On);
+
+        assert!(canvas.data()[120..128].iter().all(|b| *b == 0x01));
+        assert_eq!(canvas.data()[128], 0x00);
+        assert_eq!(canvas.data()[7 * 128], 0xF0);
+    }
+
+    #[test]
+    fn draw_rect_is_truncated_at_edge() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.draw_rect(120, 0, 20, 4, Pixel::On);
+
+        assert_eq!(canvas.data()[120], 0x0F);
+        assert!(canvas.data()[121..128].iter().all(|b| *b == 0x09));
+        assert_eq!(canvas.data()[128], 0x00);
+    }
+
+    #[test]
+    fn fill_rect_spans_row_bands() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.fill_rect(2, 6, 3, 4, Pixel::On);
+
+        for x in 2..5 {
+            assert_eq!(canvas.data()[x], 0xC0);
+            assert_eq!(canvas.data()[128 + x], 0x03);
+        }
+        assert_eq!(canvas.data()[1], 0x00);
+        assert_eq!(canvas.data()[5], 0x00);
+
+        canvas.fill_rect(3, 7, 1, 2, Pixel::Off);
+        assert_eq!(canvas.data()[3], 0x40);
+        assert_eq!(canvas.data()[128 + 3], 0x02);
+    }
+
+    #[test]
+    fn fill_rect_is_clipped() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.fill_rect(120, 60, 20, 20, Pixel::On);
+
+        assert!(canvas.data()[(7 * 128 + 120)..].iter().all(|b| *b == 0xF0));
+        assert_eq!(canvas.data()[7 * 128 + 119], 0x00);
+    }
+
+    #[test]
+    fn hscroll_left_clears_last_column() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.set_pixel(0, 0, Pixel::On);
+        canvas.set_pixel(5, 63, Pixel::On);
+        canvas.hscroll(0, 128, HorizontalDirection::Left, false);
+
+        assert_eq!(canvas.data()[0], 0x00);
+        assert_eq!(canvas.data()[127], 0x00);
+        assert_eq!(canvas.data()[7 * 128 + 4], 0x80);
+    }
+
+    #[test]
+    fn hscroll_right_wraps() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.set_pixel(127, 1, Pixel::On);
+        canvas.set_pixel(10, 9, Pixel::On);
+        canvas.hscroll(0, 128, HorizontalDirection::Right, true);
+
+        assert_eq!(canvas.data()[0], 0x02);
+        assert_eq!(canvas.data()[127], 0x00);
+        assert_eq!(canvas.data()[128 + 11], 0x02);
+    }
+
+    #[test]
+    fn print_aligned_right() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.print_aligned("!!", 0, &crate::fonts::FONT_NX5, Pixel::On, Align::Right);
+
+        // Two 1 pixel wide glyphs with a single pixel of spacing
+        assert_eq!(canvas.data()[125], 23 << 2);
+        assert_eq!(canvas.data()[126], 0x00);
+        assert_eq!(canvas.data()[127], 23 << 2);
+    }
+
+    #[test]
+    fn print_aligned_center_per_line() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.print_aligned(
+            "!\n!!!",
+            0,
+            &crate::fonts::FONT_NX5,
+            Pixel::On,
+            Align::Center,
+        );
+
+        assert_eq!(canvas.data()[63], 23 << 2);
+        assert_eq!(canvas.data()[128 + 61], 23 << 2);
+        assert_eq!(canvas.data()[128 + 65], 23 << 2);
+    }
+
+    #[test]
+    fn print_wrapped_breaks_on_whitespace() {
+        let mut canvas = MonochromeCanvas::new(16, 16);
+        let rows = canvas.print_wrapped("!!!! !!!!", 0, 0, &crate::fonts::FONT_NX5, Pixel::On);
+
+        assert_eq!(rows, 2);
+        assert_eq!(canvas.data()[6], 23 << 2);
+        assert_eq!(canvas.data()[8], 0x00);
+        assert_eq!(canvas.data()[16 + 6], 23 << 2);
+    }
+
+    #[test]
+    fn print_wrapped_splits_long_words() {
+        let mut canvas = MonochromeCanvas::new(16, 16);
+        let rows = canvas.print_wrapped("!!!!!!!!!!", 0, 0, &crate::fonts::FONT_NX5, Pixel::On);
+
+        assert_eq!(rows, 2);
+        assert_eq!(canvas.data()[14], 23 << 2);
+        assert_eq!(canvas.data()[16], 23 << 2);
+        assert_eq!(canvas.data()[16 + 2], 23 << 2);
+        assert_eq!(canvas.data()[16 + 4], 0x00);
+    }
+
+    #[test]
+    fn set_pixel_out_of_bounds_is_ignored() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.set_pixel(128, 0, Pixel::On);
+        canvas.set_pixel(0, 64, Pixel::On);
+
+        assert!(canvas.data().iter().all(|b| *b == 0x00));
+        assert!(canvas.pixel(128, 0).is_none());
+        assert!(canvas.pixel(0, 64).is_none());
+    }
+
+    #[test]
+    fn pixel_round_trip_within_band() {
+        for bit in 0..8 {
+            let mut canvas = MonochromeCanvas::new(128, 64);
+            canvas.set_pixel(3, 8 + bit, Pixel::On);
+
+            for y in 8..16 {
+                let expected = y == 8 + bit;
+                assert_eq!(matches!(canvas.pixel(3, y), Some(Pixel::On)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn rgb_set_pixel_packs_bytes() {
+        let mut canvas = RgbCanvas::new(4, 8);
+        canvas.set_pixel(1, 1, Colour::new(1, 2, 3));
+
+        assert_eq!(canvas.data_size(), 4 * 8 * 3);
+        assert_eq!(&canvas.data()[15..18], &[1, 2, 3]);
+        assert_eq!(canvas.pixel(1, 1), Some(Colour::new(1, 2, 3)));
+        assert_eq!(canvas.pixel(4, 1), None);
+    }
+
+    #[test]
+    fn rgb_fill_and_print() {
+        let mut canvas = RgbCanvas::new(8, 16);
+        canvas.fill_row(1, Colour::BLUE);
+        assert_eq!(canvas.pixel(0, 7), Some(Colour::BLACK));
+        assert_eq!(canvas.pixel(7, 15), Some(Colour::BLUE));
+
+        // '!' is a single column glyph
+        assert_eq!(
+            canvas.print_char('!', 0, 2, &crate::fonts::FONT_NX5, Colour::RED),
+            1
+        );
+        let lit = (0..8).filter(|y| canvas.pixel(2, *y) == Some(Colour::RED));
+        assert_eq!(lit.count(), 4);
+    }
+
+    #[test]
+    fn rgb_hscroll() {
+        let mut canvas = RgbCanvas::new(4, 8);
+        canvas.set_pixel(0, 0, Colour::RED);
+        canvas.hscroll(0, 4, HorizontalDirection::Right, false);
+        assert_eq!(canvas.pixel(1, 0), Some(Colour::RED));
+        assert_eq!(canvas.pixel(0, 0), Some(Colour::BLACK));
+
+        canvas.hscroll(0, 2, HorizontalDirection::Left, true);
+        canvas.hscroll(0, 2, HorizontalDirection::Left, true);
+        assert_eq!(canvas.pixel(1, 0), Some(Colour::RED));
+    }
+
+    #[test]
+    fn double_buffered_present() {
+        let mut canvas = DoubleBuffered::monochrome(16, 8);
+        canvas.clear_dirty_flag();
+        canvas.set_pixel(0, 0, Pixel::On);
+
+        // Nothing is visible until presented
+        assert!(!canvas.is_dirty());
+        assert_eq!(canvas.data()[0], 0x00);
+
+        canvas.present();
+        assert!(canvas.is_dirty());
+        assert_eq!(canvas.data()[0], 0x01);
+        assert_eq!(canvas.front().data()[0], 0x01);
+    }
+
+    #[test]
+    fn dirty_rows() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        assert_eq!(canvas.dirty_rows().count(), 8);
+
+        canvas.clear_dirty_flag();
+        assert!(!canvas.is_dirty());
+
+        canvas.set_pixel(3, 20, Pixel::On);
+        canvas.fill_rect(0, 40, 4, 10, Pixel::On);
+        assert_eq!(canvas.dirty_rows().collect::<Vec<usize>>(), vec![2, 5, 6]);
+        assert!(canvas.is_row_dirty(2));
+        assert!(!canvas.is_row_dirty(3));
+    }
+
+    #[test]
+    fn flip_horizontal() {
+        let mut canvas = MonochromeCanvas::new(16, 16);
+        canvas.set_pixel(0, 0, Pixel::On);
+        canvas.set_pixel(2, 9, Pixel::On);
+        canvas.flip_horizontal();
+
+        assert!(matches!(canvas.pixel(15, 0), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(13, 9), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(0, 0), Some(Pixel::Off)));
+        assert!(canvas.is_dirty());
+    }
+
+    #[test]
+    fn flip_vertical() {
+        let mut canvas = MonochromeCanvas::new(16, 24);
+        canvas.set_pixel(0, 0, Pixel::On);
+        canvas.set_pixel(5, 10, Pixel::On);
+        canvas.flip_vertical();
+
+        assert!(matches!(canvas.pixel(0, 23), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(5, 13), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(0, 0), Some(Pixel::Off)));
+        assert!(matches!(canvas.pixel(5, 10), Some(Pixel::Off)));
+    }
+
+    #[test]
+    fn flip_default_matches_monochrome() {
+        let mut canvas = RgbCanvas::new(4, 16);
+        canvas.set_pixel(0, 1, Colour::RED);
+        canvas.flip_horizontal();
+        canvas.flip_vertical();
+
+        assert_eq!(canvas.pixel(3, 14), Some(Colour::RED));
+        assert_eq!(canvas.pixel(0, 1), Some(Colour::BLACK));
+    }
+
+    #[test]
+    fn rotate_180_glyph() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.print("!", 0, 0, &crate::fonts::FONT_NX5, Pixel::On);
+        canvas.clear_dirty_flag();
+        canvas.rotate_180();
+
+        // Glyph 0b0101_1100 read bottom to top
+        assert_eq!(canvas.data()[7 * 128 + 127], 0b0011_1010);
+        assert_eq!(canvas.data()[0], 0x00);
+        assert!(canvas.is_dirty());
+
+        let mut flipped = MonochromeCanvas::new(128, 64);
+        flipped.print("!", 0, 0, &crate::fonts::FONT_NX5, Pixel::On);
+        flipped.flip_horizontal();
+        flipped.flip_vertical();
+        assert_eq!(canvas.data(), flipped.data());
+    }
+
+    #[test]
+    fn viewport_clips_drawing() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        {
+            let mut view = Viewport::new(&mut canvas, 10, 4, 4, 8);
+            view.set_pixel(0, 0, Pixel::On);
+            view.set_pixel(4, 0, Pixel::On);
+            view.fill_rect(2, 6, 10, 10, Pixel::On);
+            view.print("!!!", 0, 0, &crate::fonts::FONT_NX5, Pixel::On);
+            assert!(view.pixel::<Pixel>(4, 0).is_none());
+        }
+
+        assert!(matches!(canvas.pixel(10, 4), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(14, 4), Some(Pixel::Off)));
+        assert!(matches!(canvas.pixel(13, 11), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(14, 11), Some(Pixel::Off)));
+        assert!(matches!(canvas.pixel(13, 12), Some(Pixel::Off)));
+        // Third glyph falls outside of the viewport
+        assert!(matches!(canvas.pixel(12, 6), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(14, 6), Some(Pixel::Off)));
+    }
+
+    #[test]
+    fn draw_progress_bar() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.draw_progress_bar(0, 0, 12, 8, 0.5, Pixel::On);
+
+        assert_eq!(canvas.data()[0], 0xFF);
+        assert!(canvas.data()[1..6].iter().all(|b| *b == 0xFF));
+        assert!(canvas.data()[6..11].iter().all(|b| *b == 0x81));
+        assert_eq!(canvas.data()[11], 0xFF);
+
+        canvas.draw_progress_bar(0, 8, 12, 8, 2.0, Pixel::On);
+        assert!(canvas.data()[128..140].iter().all(|b| *b == 0xFF));
+    }
+
+    #[test]
+    fn text_width_sums_glyphs_and_spacing() {
+        let font = &crate::fonts::FONT_NX5;
+
+        assert_eq!(text_width("", font), 0);
+        assert_eq!(text_width("!", font), 1);
+        assert_eq!(text_width("!!!", font), 5);
+        assert_eq!(text_width("!\u{7}!", font), 3);
+        assert_eq!(text_width("!\n!!", font), 3);
+    }
+
+    #[test]
+    fn print_char_at_within_band() {
+        let mut canvas = MonochromeCanvas::new(16, 16);
+        canvas.fill_rect(0, 0, 16, 1, Pixel::On);
+        canvas.clear_dirty_flag();
+        let width = canvas.print_char_at('!', 4, 3, &crate::fonts::FONT_NX5, Pixel::On);
+
+        assert_eq!(width, 1);
+        // Existing pixels are kept
+        assert_eq!(canvas.data()[4], (23 << 3) | 0x01);
+        assert_eq!(canvas.data()[16 + 4], 0x00);
+        assert!(canvas.is_row_dirty(0));
+        assert!(!canvas.is_row_dirty(1));
+    }
+
+    #[test]
+    fn print_char_at_across_bands() {
+        let mut canvas = MonochromeCanvas::new(16, 24);
+        canvas.print_char_at('!', 0, 12, &crate::fonts::FONT_NX5, Pixel::On);
+
+        // 0b1_0111 shifted down 4 pixels into the second band
+        assert_eq!(canvas.data()[16], 0x70);
+        assert_eq!(canvas.data()[32], 0x01);
+        assert_eq!(canvas.data()[0], 0x00);
+
+        canvas.print_char_at('!', 0, 12, &crate::fonts::FONT_NX5, Pixel::Off);
+        assert!(canvas.data().iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn print_returns_end_cursor() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        let font = &crate::fonts::FONT_NX5;
+
+        assert_eq!(canvas.print("!!", 0, 0, font, Pixel::On), (0, 4));
+        assert_eq!(canvas.print("!\n!", 1, 10, font, Pixel::On), (2, 2));
+
+        let (row, col) = canvas.print("!", 3, 0, font, Pixel::On);
+        canvas.print("!", row, col, font, Pixel::Off);
+        assert_eq!(canvas.data()[(3 * 128) + 2], !(23 << 2));
+    }
+
+    #[test]
+    fn invert_rect_spans_row_bands() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.fill_rect(0, 0, 128, 8, Pixel::On);
+        canvas.clear_dirty_flag();
+        canvas.invert_rect(2, 6, 3, 4);
+
+        for x in 2..5 {
+            assert_eq!(canvas.data()[x], 0x3F);
+            assert_eq!(canvas.data()[128 + x], 0x03);
+        }
+        assert_eq!(canvas.data()[5], 0xFF);
+        assert!(canvas.is_row_dirty(0) && canvas.is_row_dirty(1));
+        assert!(!canvas.is_row_dirty(2));
+
+        canvas.invert_rect(120, 60, 20, 20);
+        assert!(canvas.data()[(7 * 128 + 120)..].iter().all(|b| *b == 0xF0));
+    }
+
+    #[test]
+    fn invert_row_marks_dirty() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.clear_dirty_flag();
+        canvas.invert_row(2);
+
+        assert!(canvas.is_dirty());
+        assert!(canvas.is_row_dirty(2));
+        assert_eq!(canvas.data()[2 * 128], 0xFF);
+
+        canvas.clear_dirty_flag();
+        canvas.invert_row_slice(5, 10, 20);
+
+        assert!(canvas.is_dirty());
+        assert!(canvas.is_row_dirty(5));
+        assert!(!canvas.is_row_dirty(2));
+    }
+
+    #[test]
+    fn fill_rows_is_clamped() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.fill_rows(6, 20, Pixel::On);
+
+        assert!(canvas.data()[(6 * 128)..].iter().all(|b| *b == 0xFF));
+        assert!(canvas.data()[..(6 * 128)].iter().all(|b| *b == 0x00));
+
+        canvas.fill_rows(12, 20, Pixel::Off);
+        canvas.fill_rows(5, 2, Pixel::Off);
+        assert_eq!(canvas.data()[7 * 128], 0xFF);
+    }
+
+    #[test]
+    fn vscroll_rows_is_clamped() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.fill_row(6, Pixel::On);
+        canvas.vscroll_rows(0, 100, Direction::Up);
+
+        assert_eq!(canvas.data()[7 * 128], 0xFF);
+        assert_eq!(canvas.data()[6 * 128], 0x00);
+
+        canvas.vscroll_rows(50, 100, Direction::Down);
+        assert_eq!(canvas.data()[7 * 128], 0xFF);
+
+        let mut canvas = RgbCanvas::new(4, 16);
+        canvas.fill_row(0, Colour::RED);
+        canvas.vscroll_rows(0, 9, Direction::Up);
+        assert_eq!(canvas.pixel(0, 8), Some(Colour::RED));
+        canvas.fill_rows(1, 9, Colour::BLUE);
+        canvas.fill_rows(9, 1, Colour::BLUE);
+        assert_eq!(canvas.pixel(0, 8), Some(Colour::BLUE));
+    }
+
+    #[test]
+    fn to_ascii() {
+        let mut canvas = MonochromeCanvas::new(4, 8);
+        canvas.set_pixel(0, 0, Pixel::On);
+        canvas.set_pixel(3, 7, Pixel::On);
+
+        let ascii = canvas.to_ascii();
+        let lines: Vec<&str> = ascii.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], "\u{2588}   ");
+        assert_eq!(lines[1], "    ");
+        assert_eq!(lines[7], "   \u{2588}");
+    }
+
+    #[test]
+    fn print_default_uses_default_font() {
+        let mut canvas = MonochromeCanvas::new(16, 8);
+        let mut expected = MonochromeCanvas::new(16, 8);
+
+        assert_eq!(canvas.print_default("!", 0, 0, Pixel::On), (0, 2));
+        expected.print("!", 0, 0, &crate::fonts::DEFAULT_FONT, Pixel::On);
+        assert_eq!(canvas.data(), expected.data());
+    }
+
+    #[test]
+    fn copy_from_mismatched_size_copies_overlap() {
+        let mut source = MonochromeCanvas::new(8, 16);
+        source.fill(Pixel::On);
+        let mut canvas = MonochromeCanvas::new(16, 8);
+        canvas.clear_dirty_flag();
+
+        canvas.copy_from(&source);
+
+        assert_eq!(canvas.data().len(), 16);
+        assert!(canvas.data()[..8].iter().all(|b| *b == 0xFF));
+        assert!(canvas.data()[8..].iter().all(|b| *b == 0x00));
+        assert!(canvas.is_dirty());
+    }
+
+    #[test]
+    fn copy_from_same_size_marks_dirty() {
+        let mut source = RgbCanvas::new(2, 2);
+        source.set_pixel(1, 1, Colour::RED);
+        let mut canvas = RgbCanvas::new(2, 2);
+        canvas.clear_dirty_flag();
+
+        canvas.copy_from(&source);
+
+        assert_eq!(canvas.data(), source.data());
+        assert!(canvas.is_dirty());
+    }
+
+    #[test]
+    fn copy_region_clips_to_both_canvases() {
+        let mut source = MonochromeCanvas::new(16, 16);
+        source.fill_rect(8, 8, 8, 8, Pixel::On);
+        let mut canvas = MonochromeCanvas::new(16, 8);
+
+        canvas.copy_region(&source, 8, 8, 16, 16, 12, 4);
+
+        assert!(matches!(canvas.pixel(12, 4), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(15, 7), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(11, 4), Some(Pixel::Off)));
+        assert!(matches!(canvas.pixel(12, 3), Some(Pixel::Off)));
+
+        canvas.copy_region(&source, 20, 0, 4, 4, 0, 0);
+        assert!(matches!(canvas.pixel(0, 0), Some(Pixel::Off)));
+    }
+
+    #[test]
+    fn viewport_of_boxed_canvas() {
+        let mut canvas: BoxedCanvas<Pixel> = Box::new(MonochromeCanvas::new(16, 8));
+        let mut view = Viewport::new(canvas.as_mut(), 4, 0, 4, 8);
+        view.fill(Pixel::On);
+
+        assert!(matches!(canvas.pixel(4, 0), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(3, 0), Some(Pixel::Off)));
+    }
+
+    #[test]
+    fn fill_checkerboard_alternates_squares() {
+        let mut canvas = MonochromeCanvas::new(8, 16);
+        canvas.clear_dirty_flag();
+        canvas.fill_checkerboard(4);
+
+        assert_eq!(
+            &canvas.data()[..8],
+            &[0x0F, 0x0F, 0x0F, 0x0F, 0xF0, 0xF0, 0xF0, 0xF0]
+        );
+        assert!(matches!(canvas.pixel(0, 8), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(4, 8), Some(Pixel::Off)));
+        assert!(canvas.is_dirty());
+
+        canvas.fill_checkerboard(1);
+        assert_eq!(&canvas.data()[..2], &[0x55, 0xAA]);
+    }
+
+    #[test]
+    fn fill_pattern_repeats_bytes() {
+        let mut canvas = MonochromeCanvas::new(8, 16);
+        canvas.clear_dirty_flag();
+        canvas.fill_pattern(0x55);
+
+        assert!(canvas.data().iter().all(|b| *b == 0x55));
+        assert!(canvas.is_dirty());
+    }
+
+    #[test]
+    fn print_aligned_truncates_wide_lines() {
+        let font = &crate::fonts::FONT_NX5;
+        let line = "W".repeat(60);
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.print_aligned(&line, 7, font, Pixel::On, Align::Center);
+
+        let fitted = fit_line(&line, font, 128);
+        assert!(text_width(fitted, font) <= 128);
+        assert!(text_width(&format!("{}W", fitted), font) > 128);
+        assert!(canvas.data()[..7 * 128].iter().all(|b| *b == 0));
+
+        let mut right = MonochromeCanvas::new(128, 64);
+        right.print_aligned(&line, 7, font, Pixel::On, Align::Right);
+        let spare = 128 - text_width(fitted, font);
+        assert_ne!(right.data()[7 * 128 + spare], 0);
+        assert_ne!(right.data()[8 * 128 - 1], 0);
+    }
+
+    #[test]
+    fn print_char_clips_to_canvas() {
+        let font = &crate::fonts::FONT_NX5;
+        let mut canvas = MonochromeCanvas::new(8, 16);
+
+        assert_eq!(canvas.print_char('#', 0, 6, font, Pixel::On), 5);
+        assert_eq!(canvas.print_char('#', 2, 0, font, Pixel::On), 5);
+        assert_ne!(canvas.data()[7], 0);
+        assert!(canvas.data()[8..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn print_wrapped_stops_at_last_row() {
+        let font = &crate::fonts::FONT_NX5;
+        let text = "hello world ".repeat(40);
+        let mut canvas = MonochromeCanvas::new(128, 64);
+
+        assert_eq!(canvas.print_wrapped(&text, 6, 0, font, Pixel::On), 2);
+        assert!(canvas.data()[6 * 128..].iter().any(|b| *b != 0));
+        assert_eq!(canvas.print_wrapped(&text, 8, 0, font, Pixel::On), 0);
+
+        let mut narrow = MonochromeCanvas::new(4, 16);
+        assert_eq!(narrow.print_wrapped("WW", 0, 2, font, Pixel::On), 2);
+    }
+
+    ///
+    /// Greyscale image with a luminance level for each pixel
+    ///
+    struct Levels {
+        width: usize,
+        levels: Vec<u8>,
+    }
+
+    impl Image for Levels {
+        fn width(&self) -> usize {
+            self.width
+        }
+
+        fn height(&self) -> usize {
+            self.levels.len() / self.width
+        }
+
+        fn pixel(&self, x: usize, y: usize) -> Colour {
+            let level = self.levels[(y * self.width) + x];
+            Colour::new(level, level, level)
+        }
+    }
+
+    #[test]
+    fn blit_bitmap_threshold_boundary() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        let bmp = Levels {
+            width: 3,
+            levels: vec![0x7F, 0x80, 0x81],
+        };
+        canvas.blit_bitmap(&bmp, 0, 0, 0x80);
+
+        assert!(matches!(canvas.pixel(0, 0), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(1, 0), Some(Pixel::Off)));
+        assert!(matches!(canvas.pixel(2, 0), Some(Pixel::Off)));
+    }
+
+    #[test]
+    fn blit_bitmap_clips_right_and_bottom() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        let bmp = Levels {
+            width: 8,
+            levels: vec![0; 64],
+        };
+        canvas.blit_bitmap(&bmp, 124, 60, 0x80);
+
+        let set = canvas.data().iter().map(|b| b.count_ones()).sum::<u32>();
+        assert_eq!(set, 16);
+        assert!(matches!(canvas.pixel(124, 60), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(127, 63), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(123, 63), Some(Pixel::Off)));
+
+        // Entirely off the canvas
+        canvas.blit_bitmap(&bmp, 128, 64, 0x80);
+        assert_eq!(
+            canvas.data().iter().map(|b| b.count_ones()).sum::<u32>(),
+            16
+        );
+    }
+
+    #[test]
+    fn blit_bitmap_across_band_boundary() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        let bmp = Levels {
+            width: 4,
+            levels: vec![0; 16],
+        };
+        canvas.blit_bitmap(&bmp, 10, 6, 0x80);
+
+        // Rows 6 and 7 are in the first band, rows 8 and 9 in the second
+        for x in 10..14 {
+            assert_eq!(canvas.data()[x], 0b1100_0000);
+            assert_eq!(canvas.data()[128 + x], 0b0000_0011);
+        }
+        assert_eq!(canvas.data()[9], 0);
+        assert_eq!(canvas.data()[14], 0);
+        assert_eq!(canvas.data()[128 + 14], 0);
+    }
+
+    #[test]
+    fn draw_line_is_clipped_to_canvas() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.draw_line(0, 0, usize::MAX, usize::MAX, Pixel::On);
+        assert!(matches!(canvas.pixel(0, 0), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(63, 63), Some(Pixel::On)));
+        assert_eq!(
+            canvas.data().iter().map(|b| b.count_ones()).sum::<u32>(),
+            64
+        );
+
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.draw_line(500, 10, 100, 10, Pixel::On);
+        for x in 0..128 {
+            let on = matches!(canvas.pixel(x, 10), Some(Pixel::On));
+            assert_eq!(on, x >= 100, "pixel ({}, 10)", x);
+        }
+
+        // Entirely off the canvas
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.draw_line(200, 0, 200, 63, Pixel::On);
+        assert!(canvas.data().iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn draw_rect_does_not_overflow() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.draw_rect(120, 60, usize::MAX, usize::MAX, Pixel::On);
+
+        assert!(matches!(canvas.pixel(120, 60), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(127, 60), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(120, 63), Some(Pixel::On)));
+        assert!(matches!(canvas.pixel(121, 61), Some(Pixel::Off)));
+
+        canvas.draw_rect(usize::MAX, usize::MAX, 2, 2, Pixel::On);
+    }
+
+    #[test]
+    fn rgb_invert_row_slice_is_clipped() {
+        let mut canvas = RgbCanvas::new(128, 64);
+        canvas.invert_row_slice(7, 200, 300);
+        assert!(canvas.data().iter().all(|b| *b == 0));
+
+        canvas.invert_row_slice(7, 120, 300);
+        assert_eq!(canvas.pixel(119, 63), Some(Colour::BLACK));
+        assert_eq!(canvas.pixel(120, 56), Some(Colour::WHITE));
+        assert_eq!(canvas.pixel(127, 63), Some(Colour::WHITE));
+        assert_eq!(canvas.pixel(127, 55), Some(Colour::BLACK));
+    }
+}
@@ -0,0 +1,119 @@
+use crate::events::{Button, Direction, Event};
+
+///
+/// Interaction with the main encoder
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncoderGesture {
+    /// Encoder turned (Direction, Steps)
+    Turn(Direction, u8),
+
+    /// Encoder turned while pushed in, eg for fine adjustment (Direction, Steps)
+    PushTurn(Direction, u8),
+
+    /// Encoder pushed and released without being turned
+    Click,
+}
+
+///
+/// Distinguish turning, push-and-turn and clicking the main encoder
+///
+/// The encoder button and encoder turns are reported as separate events, this follows the
+/// state of the button so a turn while it is held is reported as `PushTurn` and releasing it
+/// is only reported as a `Click` when the encoder was not turned in between.
+///
+/// ```
+/// use maschine::{Button, Direction, EncoderGesture, EncoderState, Event};
+///
+/// let mut encoder = EncoderState::new();
+/// encoder.handle_event(&Event::Button(Button::MainEncoder, true, false));
+///
+/// let gesture = encoder.handle_event(&Event::Encoder(0, Direction::Up, 1, false));
+/// assert_eq!(gesture, Some(EncoderGesture::PushTurn(Direction::Up, 1)));
+/// ```
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EncoderState {
+    pressed: bool,
+    turned_while_pressed: bool,
+}
+
+impl EncoderState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Determine if the encoder is currently pushed in
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    ///
+    /// Interpret an event, returns the gesture completed by an encoder event
+    ///
+    pub fn handle_event(&mut self, event: &Event) -> Option<EncoderGesture> {
+        match event {
+            Event::Button(Button::MainEncoder, true, _) => {
+                self.pressed = true;
+                self.turned_while_pressed = false;
+                None
+            }
+            Event::Button(Button::MainEncoder, false, _) => {
+                let clicked = self.pressed && !self.turned_while_pressed;
+                self.pressed = false;
+                clicked.then_some(EncoderGesture::Click)
+            }
+            Event::Encoder(0, direction, steps, _) => {
+                if self.pressed {
+                    self.turned_while_pressed = true;
+                    Some(EncoderGesture::PushTurn(*direction, *steps))
+                } else {
+                    Some(EncoderGesture::Turn(*direction, *steps))
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoder_button(pressed: bool) -> Event {
+        Event::Button(Button::MainEncoder, pressed, false)
+    }
+
+    #[test]
+    fn turn_and_click() {
+        let mut encoder = EncoderState::new();
+
+        assert_eq!(
+            encoder.handle_event(&Event::Encoder(0, Direction::Down, 2, false)),
+            Some(EncoderGesture::Turn(Direction::Down, 2))
+        );
+        assert_eq!(encoder.handle_event(&encoder_button(true)), None);
+        assert!(encoder.is_pressed());
+        assert_eq!(
+            encoder.handle_event(&encoder_button(false)),
+            Some(EncoderGesture::Click)
+        );
+    }
+
+    #[test]
+    fn push_turn_is_not_a_click() {
+        let mut encoder = EncoderState::new();
+
+        encoder.handle_event(&encoder_button(true));
+        assert_eq!(
+            encoder.handle_event(&Event::Encoder(0, Direction::Up, 1, false)),
+            Some(EncoderGesture::PushTurn(Direction::Up, 1))
+        );
+        assert_eq!(encoder.handle_event(&encoder_button(false)), None);
+        assert!(!encoder.is_pressed());
+        assert_eq!(
+            encoder.handle_event(&Event::Button(Button::Play, true, false)),
+            None
+        );
+    }
+}
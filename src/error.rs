@@ -13,6 +13,43 @@ pub enum Error {
 
     /// Unexpected control returned from hardware device
     UnknownControl,
+
+    /// Colour string could not be parsed
+    InvalidColour,
+
+    /// Display data is not the size expected by the device
+    InvalidFrame,
+
+    /// Device is not a supported controller
+    UnsupportedDevice,
+
+    /// Device can no longer be read from or written to (eg it has been unplugged), holds the
+    /// error reported by HIDAPI
+    Disconnected(HidError),
+}
+
+impl Error {
+    ///
+    /// Error indicates the device has gone and should be reconnected
+    ///
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self, Error::Disconnected(_))
+    }
+
+    ///
+    /// Convert an error from reading or writing an open device
+    ///
+    /// HIDAPI reports a device that has gone away as a generic failure of the read or write
+    /// call, so these are treated as a disconnection. The original error is kept for reporting.
+    ///
+    pub(crate) fn from_device_io(err: HidError) -> Error {
+        match err {
+            err @ (HidError::HidApiError { .. } | HidError::HidApiErrorEmpty) => {
+                Error::Disconnected(err)
+            }
+            err => Error::HidAPI(err),
+        }
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -25,6 +62,18 @@ impl std::fmt::Display for Error {
             Error::UnknownControl => {
                 write!(fmt, "Unexpected control returned from hardware device")
             }
+            Error::InvalidColour => {
+                write!(fmt, "Colour is not a valid #RRGGBB or #RGB hex string")
+            }
+            Error::InvalidFrame => {
+                write!(fmt, "Display data is not the size expected by the device")
+            }
+            Error::UnsupportedDevice => {
+                write!(fmt, "Device is not a supported controller")
+            }
+            Error::Disconnected(e) => {
+                write!(fmt, "Device has been disconnected: {}", e)
+            }
         }
     }
 }
@@ -34,3 +83,36 @@ impl From<HidError> for Error {
         Error::HidAPI(err)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_io_failures_are_disconnections() {
+        let err = Error::from_device_io(HidError::HidApiError {
+            message: "No such device".into(),
+        });
+        assert!(err.is_disconnected());
+        assert!(err.to_string().ends_with("No such device"));
+
+        assert!(Error::from_device_io(HidError::HidApiErrorEmpty).is_disconnected());
+    }
+
+    #[test]
+    fn other_device_io_errors_are_kept() {
+        let err = Error::from_device_io(HidError::InvalidZeroSizeData);
+        assert!(matches!(err, Error::HidAPI(HidError::InvalidZeroSizeData)));
+        assert!(!err.is_disconnected());
+
+        let err = Error::from_device_io(HidError::IncompleteSendError { sent: 1, all: 2 });
+        assert!(!err.is_disconnected());
+    }
+
+    #[test]
+    fn only_disconnected_is_disconnected() {
+        assert!(!Error::InvalidReport.is_disconnected());
+        assert!(!Error::HidAPI(HidError::HidApiErrorEmpty).is_disconnected());
+        assert!(Error::Disconnected(HidError::HidApiErrorEmpty).is_disconnected());
+    }
+}
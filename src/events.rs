@@ -1,10 +1,32 @@
 use crate::error::Error;
 use std::collections::VecDeque;
+use std::time::Instant;
 
 ///
 /// System Events
 ///
-#[derive(Debug, Copy, Clone)]
+/// ```
+/// use maschine::{Direction, Event};
+///
+/// fn describe(event: &Event) -> String {
+///     match event {
+///         Event::Button(button, pressed, _) => format!("{:?} pressed: {}", button, pressed),
+///         Event::Encoder(encoder, Direction::Up, _, _) => format!("Encoder {} up", encoder),
+///         Event::Encoder(encoder, _, _, _) => format!("Encoder {} down", encoder),
+///         Event::Pad(pad, velocity, _) => format!("Pad {} velocity: {}", pad, velocity),
+///         Event::PadRelease(pad, _) => format!("Pad {} released", pad),
+///         Event::ButtonLongPress(button, _) => format!("{:?} held", button),
+///         Event::PadDoubleTap(pad, _, _) => format!("Pad {} double tapped", pad),
+///         Event::PadAftertouch(pad, pressure, _) => format!("Pad {} pressure: {}", pad, pressure),
+///         Event::PadChord(pads, _) => format!("{} pad chord", pads.len()),
+///     }
+/// }
+///
+/// assert_eq!(describe(&Event::Pad(3, 127, false)), "Pad 3 velocity: 127");
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(dead_code)]
 pub enum Event {
     ///
@@ -13,20 +35,72 @@ pub enum Event {
     Button(Button, bool, bool),
 
     ///
-    /// Encoder change (Encoder Number, Direction, Shift)
+    /// Encoder change (Encoder Number, Direction, Steps, Shift)
+    ///
+    /// Steps is 1 for a single detent and increases when the encoder is turned quickly.
     ///
-    Encoder(u8, Direction, bool),
+    Encoder(u8, Direction, u8, bool),
 
     ///
-    /// Pad change (Pad Number, Velocity, Shift)
+    /// Pad struck (Pad Number, Velocity, Shift)
     ///
     Pad(u8, u8, bool),
+
+    ///
+    /// Pad released (Pad Number, Shift)
+    ///
+    PadRelease(u8, bool),
+
+    ///
+    /// Button held beyond the long press threshold (Button, Shift)
+    ///
+    ButtonLongPress(Button, bool),
+
+    ///
+    /// Pad struck twice within the double tap interval (Pad Number, Velocity, Shift)
+    ///
+    PadDoubleTap(u8, u8, bool),
+
+    ///
+    /// Pressure change of a held pad, 12bit value (Pad Number, Pressure, Shift)
+    ///
+    PadAftertouch(u8, u16, bool),
+
+    ///
+    /// Multiple pads struck within the chord window (Pad Numbers and Velocities, Shift)
+    ///
+    PadChord(Vec<(u8, u8)>, bool),
+}
+
+impl Event {
+    ///
+    /// Pad an event relates to, chords relate to multiple pads so have none
+    ///
+    pub fn pad(&self) -> Option<Pad> {
+        match self {
+            Event::Pad(pad, _, _)
+            | Event::PadRelease(pad, _)
+            | Event::PadDoubleTap(pad, _, _)
+            | Event::PadAftertouch(pad, _, _) => Pad::from_number(*pad),
+            _ => None,
+        }
+    }
 }
 
 ///
-/// Direction of encoder
+/// Event along with the time it was generated
 ///
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
+pub struct TimedEvent {
+    pub event: Event,
+    pub timestamp: Instant,
+}
+
+///
+/// Direction of encoder or vertical scrolling
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(dead_code)]
 pub enum Direction {
     Up,
@@ -36,7 +110,8 @@ pub enum Direction {
 ///
 /// Button Identifiers
 ///
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(dead_code)]
 pub enum Button {
     Erase,
@@ -70,25 +145,149 @@ pub enum Button {
     Unknown,
 }
 
+///
+/// Pad Identifiers, named by the number printed on the device
+///
+/// Events and `Controller::set_pad_led` use pad numbers (0x0-0xF) that start from the top left
+/// pad, `Pad13`, and convert to and from this type.
+///
+/// ```
+/// use maschine::{Event, Pad};
+///
+/// let event = Event::Pad(0xC, 100, false);
+/// assert_eq!(event.pad(), Some(Pad::Pad01));
+/// assert_eq!(u8::from(Pad::Pad01), 0xC);
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum Pad {
+    Pad13 = 0x0,
+    Pad14 = 0x1,
+    Pad15 = 0x2,
+    Pad16 = 0x3,
+    Pad09 = 0x4,
+    Pad10 = 0x5,
+    Pad11 = 0x6,
+    Pad12 = 0x7,
+    Pad05 = 0x8,
+    Pad06 = 0x9,
+    Pad07 = 0xA,
+    Pad08 = 0xB,
+    Pad01 = 0xC,
+    Pad02 = 0xD,
+    Pad03 = 0xE,
+    Pad04 = 0xF,
+}
+
+impl Pad {
+    /// All pads in pad number order
+    pub const ALL: [Pad; 16] = [
+        Pad::Pad13,
+        Pad::Pad14,
+        Pad::Pad15,
+        Pad::Pad16,
+        Pad::Pad09,
+        Pad::Pad10,
+        Pad::Pad11,
+        Pad::Pad12,
+        Pad::Pad05,
+        Pad::Pad06,
+        Pad::Pad07,
+        Pad::Pad08,
+        Pad::Pad01,
+        Pad::Pad02,
+        Pad::Pad03,
+        Pad::Pad04,
+    ];
+
+    /// Pad for a pad number, numbers beyond 0xF have no pad
+    pub fn from_number(number: u8) -> Option<Pad> {
+        Pad::ALL.get(number as usize).copied()
+    }
+
+    /// Pad number used by events and `Controller::set_pad_led`
+    pub fn number(self) -> u8 {
+        self as u8
+    }
+
+    /// Number printed on the device (1-16)
+    pub fn label(self) -> u8 {
+        let number = self as u8;
+        (3 - number / 4) * 4 + number % 4 + 1
+    }
+}
+
+impl From<Pad> for u8 {
+    fn from(pad: Pad) -> u8 {
+        pad.number()
+    }
+}
+
 ///
 /// Context object for adding events
 ///
+/// Events can either be consumed directly from the `events` queue or passed to any
+/// registered handlers by calling `dispatch`.
+///
+#[derive(Default)]
 pub struct EventContext {
-    pub events: VecDeque<Event>,
+    pub events: VecDeque<TimedEvent>,
+    handlers: Vec<Box<dyn EventHandler>>,
 }
 
 impl EventContext {
     pub fn new() -> Self {
         EventContext {
             events: VecDeque::new(),
+            handlers: Vec::new(),
         }
     }
 
     ///
-    /// Add an event into the context
+    /// Add an event into the context, timestamped with the current time
     ///
     pub fn add_event(&mut self, event: Event) {
-        self.events.push_back(event);
+        self.add_event_at(event, Instant::now());
+    }
+
+    ///
+    /// Add an event into the context that was generated at a particular time
+    ///
+    pub fn add_event_at(&mut self, event: Event, timestamp: Instant) {
+        self.events.push_back(TimedEvent { event, timestamp });
+    }
+
+    ///
+    /// Remove and iterate over the queued events in the order they were added
+    ///
+    /// The queue is empty afterwards, even if the iterator is dropped before the last event.
+    ///
+    pub fn drain(&mut self) -> impl Iterator<Item = Event> + '_ {
+        self.events.drain(..).map(|timed| timed.event)
+    }
+
+    ///
+    /// Register a handler, handlers are offered events in the order they are added
+    ///
+    pub fn add_handler(&mut self, handler: Box<dyn EventHandler>) {
+        self.handlers.push(handler);
+    }
+
+    ///
+    /// Pass each queued event to the registered handlers until one reports it as handled
+    ///
+    /// The queue is empty once dispatch returns, events no handler accepts are dropped.
+    ///
+    pub fn dispatch(&mut self) {
+        while let Some(timed) = self.events.pop_front() {
+            for handler in self.handlers.iter_mut() {
+                if handler.handle(&timed.event) {
+                    break;
+                }
+            }
+        }
     }
 }
 
@@ -102,6 +301,12 @@ pub trait EventTask {
     fn tick(&mut self, context: &mut EventContext) -> Result<(), Error>;
 }
 
+impl<T: EventTask + ?Sized> EventTask for Box<T> {
+    fn tick(&mut self, context: &mut EventContext) -> Result<(), Error> {
+        (**self).tick(context)
+    }
+}
+
 /// Handle events
 pub trait EventHandler {
     /// Handle event and return if it was handled.
@@ -111,3 +316,112 @@ pub trait EventHandler {
     ///
     fn handle(&mut self, event: &Event) -> bool;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct PadHandler(Rc<Cell<usize>>);
+
+    impl EventHandler for PadHandler {
+        fn handle(&mut self, event: &Event) -> bool {
+            match event {
+                Event::Pad(..) => {
+                    self.0.set(self.0.get() + 1);
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    struct CatchAllHandler(Rc<Cell<usize>>);
+
+    impl EventHandler for CatchAllHandler {
+        fn handle(&mut self, _event: &Event) -> bool {
+            self.0.set(self.0.get() + 1);
+            true
+        }
+    }
+
+    #[test]
+    fn pad_numbers_and_labels() {
+        for (number, pad) in Pad::ALL.iter().enumerate() {
+            assert_eq!(pad.number(), number as u8);
+            assert_eq!(Pad::from_number(number as u8), Some(*pad));
+        }
+        assert_eq!(Pad::Pad13.label(), 13);
+        assert_eq!(Pad::Pad04.label(), 4);
+        assert_eq!(Pad::from_number(16), None);
+        assert_eq!(Event::PadRelease(0x5, false).pad(), Some(Pad::Pad10));
+        assert_eq!(Event::Button(Button::Play, true, false).pad(), None);
+    }
+
+    #[test]
+    fn dispatch_stops_at_first_handler() {
+        let pads = Rc::new(Cell::new(0));
+        let others = Rc::new(Cell::new(0));
+
+        let mut context = EventContext::new();
+        context.add_handler(Box::new(PadHandler(pads.clone())));
+        context.add_handler(Box::new(CatchAllHandler(others.clone())));
+
+        context.add_event(Event::Pad(0, 100, false));
+        context.add_event(Event::Button(Button::Play, true, false));
+        context.add_event(Event::Pad(1, 100, false));
+        context.dispatch();
+
+        assert_eq!(pads.get(), 2);
+        assert_eq!(others.get(), 1);
+        assert!(context.events.is_empty());
+    }
+
+    #[test]
+    fn add_event_is_timestamped() {
+        let before = Instant::now();
+        let mut context = EventContext::new();
+        context.add_event(Event::Pad(0, 100, false));
+        context.add_event(Event::PadRelease(0, false));
+
+        let first = context.events[0].timestamp;
+        let second = context.events[1].timestamp;
+        assert!(before <= first && first <= second);
+        assert!(matches!(
+            context.events[1].event,
+            Event::PadRelease(0, false)
+        ));
+    }
+
+    #[test]
+    fn drain_empties_queue() {
+        let mut context = EventContext::new();
+        context.add_event(Event::Pad(0, 100, false));
+        context.add_event(Event::PadRelease(0, false));
+        context.add_event(Event::Pad(1, 100, false));
+
+        let first = context.drain().next();
+        assert_eq!(first, Some(Event::Pad(0, 100, false)));
+        assert!(context.events.is_empty());
+
+        context.add_event(Event::PadRelease(1, false));
+        let events: Vec<Event> = context.drain().collect();
+        assert_eq!(events, vec![Event::PadRelease(1, false)]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let events = vec![
+            Event::Button(Button::Play, true, false),
+            Event::Encoder(0, Direction::Down, 4, true),
+            Event::Pad(3, 127, false),
+            Event::PadChord(vec![(0, 100), (5, 90)], false),
+        ];
+
+        let json = serde_json::to_string(&events).unwrap();
+        assert!(json.contains(r#"{"Button":["Play",true,false]}"#));
+        assert_eq!(serde_json::from_str::<Vec<Event>>(&json).unwrap(), events);
+    }
+}
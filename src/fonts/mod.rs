@@ -1,3 +1,5 @@
+use crate::display::Font;
+
 mod nx5;
 mod nx6;
 
@@ -5,3 +7,29 @@ mod nx6;
 pub use nx5::FONT as FONT_NX5;
 #[allow(unused)]
 pub use nx6::FONT as FONT_NX6;
+
+/// Font used when none is given, eg by `Canvas::print_default`
+pub const DEFAULT_FONT: Font = FONT_NX6;
+
+/// All available fonts by name
+pub const FONTS: [(&str, &Font); 2] = [("nx5", &FONT_NX5), ("nx6", &FONT_NX6)];
+
+/// Find an available font by name
+pub fn by_name(name: &str) -> Option<&'static Font> {
+    FONTS
+        .iter()
+        .find(|(font_name, _)| *font_name == name)
+        .map(|(_, font)| *font)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fonts_found_by_name() {
+        assert!(by_name("nx5").is_some_and(|font| font[1] == FONT_NX5[1]));
+        assert!(by_name("nx6").is_some_and(|font| font[1] == FONT_NX6[1]));
+        assert!(by_name("missing").is_none());
+    }
+}
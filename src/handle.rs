@@ -0,0 +1,208 @@
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::thread::{self, JoinHandle};
+
+use crate::colour::Colour;
+use crate::controller::Controller;
+use crate::error::Error;
+use crate::events::{Button, Event, EventContext};
+
+// Work queued for the controller thread
+enum Command<C> {
+    SetButtonLed(Button, Colour),
+    SetPadLed(u8, Colour),
+    SetAllLeds(Colour),
+    Run(Box<dyn FnOnce(&mut C) + Send>),
+    Stop,
+}
+
+///
+/// Controller driven from a dedicated thread
+///
+/// The controller is opened and ticked on its own thread so blocking device IO does not hold
+/// up the application. LED changes and other work are sent to the thread as commands and
+/// events generated by the controller are received from a channel.
+///
+/// Commands are applied before each tick, so with blocking reads (the default) they wait for
+/// the next report from the device. Set a read timeout (eg `set_read_timeout`) to bound how
+/// long commands wait, or a target frame rate (`set_target_fps`) to avoid ticking a quiet
+/// device in a tight loop.
+///
+/// ```
+/// use maschine::devices::MockController;
+/// use maschine::{Colour, ControllerHandle};
+///
+/// let handle = ControllerHandle::spawn(|| Ok(MockController::new()));
+/// handle.set_pad_led(3, Colour::RED);
+/// handle.with(|ctlr| ctlr.strike_pad(3, 100));
+///
+/// let event = handle.events().recv().unwrap();
+/// assert_eq!(event.pad(), Some(maschine::Pad::Pad16));
+/// handle.stop().unwrap();
+/// ```
+///
+pub struct ControllerHandle<C> {
+    commands: Sender<Command<C>>,
+    events: Receiver<Event>,
+    thread: Option<JoinHandle<Result<(), Error>>>,
+}
+
+impl<C: Controller + 'static> ControllerHandle<C> {
+    ///
+    /// Start a thread that opens a controller and ticks it until stopped
+    ///
+    /// The controller is opened on the thread so it does not need to be `Send`, an error
+    /// opening it is returned by `stop`.
+    ///
+    pub fn spawn<F>(open: F) -> Self
+    where
+        F: FnOnce() -> Result<C, Error> + Send + 'static,
+    {
+        let (commands, command_rx) = channel();
+        let (event_tx, events) = channel();
+        let thread = thread::spawn(move || run(open()?, command_rx, event_tx));
+
+        ControllerHandle {
+            commands,
+            events,
+            thread: Some(thread),
+        }
+    }
+
+    pub fn set_button_led(&self, button: Button, colour: Colour) {
+        self.send(Command::SetButtonLed(button, colour));
+    }
+
+    pub fn set_pad_led(&self, pad: u8, colour: Colour) {
+        self.send(Command::SetPadLed(pad, colour));
+    }
+
+    pub fn set_all_leds(&self, colour: Colour) {
+        self.send(Command::SetAllLeds(colour));
+    }
+
+    ///
+    /// Run a function with the controller on its thread, eg to draw to the display
+    ///
+    pub fn with<F>(&self, f: F)
+    where
+        F: FnOnce(&mut C) + Send + 'static,
+    {
+        self.send(Command::Run(Box::new(f)));
+    }
+
+    ///
+    /// Events generated by the controller
+    ///
+    /// The channel disconnects once the controller thread has stopped (eg due to an error).
+    ///
+    pub fn events(&self) -> &Receiver<Event> {
+        &self.events
+    }
+
+    ///
+    /// Determine if the controller thread is still running
+    ///
+    pub fn is_running(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+
+    ///
+    /// Stop the controller thread, returns the error that stopped it early if any
+    ///
+    pub fn stop(mut self) -> Result<(), Error> {
+        self.join()
+    }
+
+    // Commands sent after the thread stops are dropped, the cause is reported by `stop`
+    fn send(&self, command: Command<C>) {
+        let _ = self.commands.send(command);
+    }
+
+    fn join(&mut self) -> Result<(), Error> {
+        self.send(Command::Stop);
+        match self.thread.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<C> Drop for ControllerHandle<C> {
+    fn drop(&mut self) {
+        let _ = self.commands.send(Command::Stop);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// Apply queued commands and tick the controller until stopped
+fn run<C: Controller>(
+    mut controller: C,
+    commands: Receiver<Command<C>>,
+    events: Sender<Event>,
+) -> Result<(), Error> {
+    let mut context = EventContext::new();
+    loop {
+        loop {
+            match commands.try_recv() {
+                Ok(Command::SetButtonLed(button, colour)) => {
+                    controller.set_button_led(button, colour)
+                }
+                Ok(Command::SetPadLed(pad, colour)) => controller.set_pad_led(pad, colour),
+                Ok(Command::SetAllLeds(colour)) => controller.set_all_leds(colour),
+                Ok(Command::Run(f)) => f(&mut controller),
+                Ok(Command::Stop) | Err(TryRecvError::Disconnected) => return Ok(()),
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+
+        controller.tick(&mut context)?;
+        for event in context.drain() {
+            // Events are dropped if the receiver is no longer interested
+            let _ = events.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devices::MockController;
+    use std::time::Duration;
+
+    #[test]
+    fn commands_applied_on_controller_thread() {
+        let handle = ControllerHandle::spawn(|| Ok(MockController::new()));
+        handle.set_button_led(Button::Play, Colour::GREEN);
+        handle.set_pad_led(1, Colour::BLUE);
+
+        let (tx, rx) = channel();
+        handle.with(move |ctlr| {
+            let _ = tx.send((ctlr.button_led(Button::Play), ctlr.pad_led(1)));
+        });
+        let leds = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(leds, (Colour::GREEN, Some(Colour::BLUE)));
+
+        handle.with(|ctlr| ctlr.press_button(Button::Rec, true));
+        let event = handle
+            .events()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(event, Event::Button(Button::Rec, true, false));
+
+        assert!(handle.is_running());
+        assert!(handle.stop().is_ok());
+    }
+
+    #[test]
+    fn open_error_returned_by_stop() {
+        let handle: ControllerHandle<MockController> =
+            ControllerHandle::spawn(|| Err(Error::UnsupportedDevice));
+
+        assert!(matches!(handle.stop(), Err(Error::UnsupportedDevice)));
+    }
+}
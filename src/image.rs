@@ -0,0 +1,180 @@
+use crate::colour::Colour;
+use crate::display::{Canvas, MonochromeCanvas, Pixel};
+
+///
+/// Common image behaviours
+///
+/// Implemented by each of the image loaders so images can be used interchangeably, the
+/// origin is always the top left.
+///
+pub trait Image {
+    ///
+    /// Width of the image
+    ///
+    fn width(&self) -> usize;
+
+    ///
+    /// Height of the image
+    ///
+    fn height(&self) -> usize;
+
+    ///
+    /// Colour of a pixel
+    ///
+    fn pixel(&self, x: usize, y: usize) -> Colour;
+
+    ///
+    /// Convert to a monochrome canvas using Floyd-Steinberg dithering
+    ///
+    /// The image is scaled (nearest neighbour) to the requested size, the quantisation
+    /// error of each pixel is then diffused into its neighbours so gradients render as a
+    /// stipple pattern. Dark pixels are set On.
+    ///
+    fn to_monochrome_dithered(&self, width: usize, height: usize) -> MonochromeCanvas {
+        let mut canvas = MonochromeCanvas::new(width, height);
+        if self.width() == 0 || self.height() == 0 {
+            return canvas;
+        }
+
+        let mut levels: Vec<i16> = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let colour = self.pixel(x * self.width() / width, y * self.height() / height);
+                levels.push(colour.luminance() as i16);
+            }
+        }
+
+        for y in 0..height {
+            for x in 0..width {
+                let level = levels[(y * width) + x];
+                let (value, pixel) = if level < 0x80 {
+                    (0x00, Pixel::On)
+                } else {
+                    (0xFF, Pixel::Off)
+                };
+                canvas.set_pixel(x, y, pixel);
+
+                let error = level - value;
+                let mut diffuse = |dx: isize, dy: usize, weight: i16| {
+                    let nx = x as isize + dx;
+                    let ny = y + dy;
+                    if nx >= 0 && (nx as usize) < width && ny < height {
+                        levels[(ny * width) + nx as usize] += error * weight / 16;
+                    }
+                };
+                diffuse(1, 0, 7);
+                diffuse(-1, 1, 3);
+                diffuse(0, 1, 5);
+                diffuse(1, 1, 1);
+            }
+        }
+
+        canvas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///
+    /// Image with the left half in one colour and the right half in another
+    ///
+    struct Split {
+        width: usize,
+        height: usize,
+        left: Colour,
+        right: Colour,
+    }
+
+    impl Image for Split {
+        fn width(&self) -> usize {
+            self.width
+        }
+
+        fn height(&self) -> usize {
+            self.height
+        }
+
+        fn pixel(&self, x: usize, _y: usize) -> Colour {
+            if x < self.width / 2 {
+                self.left
+            } else {
+                self.right
+            }
+        }
+    }
+
+    fn grey(level: u8) -> Colour {
+        Colour::new(level, level, level)
+    }
+
+    fn solid(level: u8) -> Split {
+        Split {
+            width: 4,
+            height: 4,
+            left: grey(level),
+            right: grey(level),
+        }
+    }
+
+    fn count_on(canvas: &MonochromeCanvas) -> usize {
+        let mut count = 0;
+        for y in 0..canvas.height() {
+            for x in 0..canvas.width() {
+                if matches!(canvas.pixel(x, y), Some(Pixel::On)) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn solid_black_is_all_on() {
+        let canvas = solid(0x00).to_monochrome_dithered(16, 16);
+        assert_eq!(count_on(&canvas), 16 * 16);
+    }
+
+    #[test]
+    fn solid_white_is_all_off() {
+        let canvas = solid(0xFF).to_monochrome_dithered(16, 16);
+        assert_eq!(count_on(&canvas), 0);
+    }
+
+    #[test]
+    fn mid_grey_is_stippled() {
+        let canvas = solid(0x80).to_monochrome_dithered(32, 32);
+
+        // Roughly half of the pixels are set
+        let count = count_on(&canvas);
+        assert!((32 * 32 * 45 / 100..=32 * 32 * 55 / 100).contains(&count));
+
+        // Spread across every row rather than in solid bands
+        for y in 0..32 {
+            let row: Vec<bool> = (0..32)
+                .map(|x| matches!(canvas.pixel(x, y), Some(Pixel::On)))
+                .collect();
+            assert!(row.contains(&true) && row.contains(&false));
+        }
+    }
+
+    #[test]
+    fn scaled_to_smaller_canvas() {
+        let image = Split {
+            width: 64,
+            height: 32,
+            left: grey(0x00),
+            right: grey(0xFF),
+        };
+        let canvas = image.to_monochrome_dithered(16, 8);
+
+        assert_eq!((canvas.width(), canvas.height()), (16, 8));
+        for y in 0..8 {
+            for x in 0..16 {
+                let on = matches!(canvas.pixel(x, y), Some(Pixel::On));
+                assert_eq!(on, x < 8, "pixel ({}, {})", x, y);
+            }
+        }
+    }
+}
@@ -1,29 +1,154 @@
-use crate::devices::MaschineMikroMk2;
+use crate::devices::{MaschineMikroMk1, MaschineMikroMk2};
 use hidapi::HidApi;
+use std::thread;
+use std::time::Duration;
 
+mod animation;
+pub mod bitmap;
 mod colour;
 mod controller;
 pub mod devices;
 mod display;
+mod encoder;
 mod error;
 mod events;
 pub mod fonts;
+mod handle;
+pub mod image;
+mod marquee;
+#[cfg(feature = "midi")]
+pub mod midi;
+#[cfg(feature = "osc")]
+pub mod osc;
+#[cfg(feature = "png")]
+pub mod png;
+mod transport;
 
-pub use colour::Colour;
-pub use controller::Controller;
-pub use display::{Canvas, Font, Pixel};
+pub use animation::{Animation, LedAnimator, LedEffect, PlayMode};
+pub use colour::{Colour, Palette};
+pub use controller::{Controller, TickPolicy};
+pub use devices::{enumerate, open_device, pad_to_xy, xy_to_pad, DeviceInfo};
+pub use display::{
+    text_width, Align, BoxedCanvas, Canvas, DoubleBuffered, Font, HorizontalDirection,
+    MonochromeCanvas, Pixel, RgbCanvas, Viewport,
+};
+pub use encoder::{EncoderGesture, EncoderState};
 pub use error::Error;
-pub use events::{Direction, Event, EventContext, EventHandler, EventTask};
+pub use events::{
+    Button, Direction, Event, EventContext, EventHandler, EventTask, Pad, TimedEvent,
+};
+pub use fonts::DEFAULT_FONT;
+pub use handle::ControllerHandle;
+pub use marquee::Marquee;
+pub use transport::{Transport, TransportState};
 
-pub fn get_device(hid_api: &HidApi) -> Result<devices::MaschineMikroMk2, error::Error> {
-    let device = hid_api
-        .open(
-            devices::MaschineMikroMk2::VENDOR_ID,
-            MaschineMikroMk2::PRODUCT_ID,
-        )
-        .expect("Cannot open device");
+///
+/// Open a connected Maschine Mikro Mk2
+///
+/// ```no_run
+/// use hidapi::HidApi;
+/// use maschine::get_device;
+///
+/// let hid_api = HidApi::new().unwrap();
+/// match get_device(&hid_api) {
+///     Ok(_ctlr) => println!("Controller connected"),
+///     Err(e) => eprintln!("No controller available: {}", e),
+/// }
+/// ```
+///
+pub fn get_device(hid_api: &HidApi) -> Result<MaschineMikroMk2, error::Error> {
+    let device = hid_api.open(MaschineMikroMk2::VENDOR_ID, MaschineMikroMk2::PRODUCT_ID)?;
 
-    Ok(devices::MaschineMikroMk2::new(device))
+    Ok(MaschineMikroMk2::new(device))
+}
+
+///
+/// Open the first connected controller
+///
+/// Known controllers are tried in order, Maschine Mikro Mk2 then Maschine Mikro Mk1. If no
+/// controller could be opened the error from the last attempt is returned.
+///
+/// ```no_run
+/// use hidapi::HidApi;
+/// use maschine::get_any_device;
+///
+/// let hid_api = HidApi::new().unwrap();
+/// match get_any_device(&hid_api) {
+///     Ok(_ctlr) => println!("Controller connected"),
+///     Err(e) => eprintln!("No controller available: {}", e),
+/// }
+/// ```
+///
+pub fn get_any_device(hid_api: &HidApi) -> Result<Box<dyn Controller>, error::Error> {
+    if let Ok(device) = get_device(hid_api) {
+        return Ok(Box::new(device));
+    }
+
+    let device = hid_api.open(MaschineMikroMk1::VENDOR_ID, MaschineMikroMk1::PRODUCT_ID)?;
+
+    Ok(Box::new(MaschineMikroMk1::new(device)))
+}
+
+///
+/// Open a connected Maschine Mikro Mk2, retrying to allow for a device that is slow to be ready
+///
+/// Up to `attempts` attempts are made (at least one) with `delay` between them, the list of HID
+/// devices is refreshed before each retry. The error from the last attempt is returned if the
+/// controller could not be opened.
+///
+/// ```no_run
+/// use hidapi::HidApi;
+/// use maschine::get_device_with_retry;
+/// use std::time::Duration;
+///
+/// let mut hid_api = HidApi::new().unwrap();
+/// let ctlr = get_device_with_retry(&mut hid_api, 5, Duration::from_millis(500));
+/// ```
+///
+pub fn get_device_with_retry(
+    hid_api: &mut HidApi,
+    attempts: u32,
+    delay: Duration,
+) -> Result<MaschineMikroMk2, error::Error> {
+    retry(hid_api, attempts, delay, get_device)
+}
+
+///
+/// Open the first connected controller, retrying to allow for a device that is slow to be ready
+///
+/// Known controllers are tried as with `get_any_device`, attempts are made as with
+/// `get_device_with_retry`.
+///
+pub fn get_any_device_with_retry(
+    hid_api: &mut HidApi,
+    attempts: u32,
+    delay: Duration,
+) -> Result<Box<dyn Controller>, error::Error> {
+    retry(hid_api, attempts, delay, get_any_device)
+}
+
+///
+/// Call `open` until it succeeds or `attempts` have been made, refreshing the list of HID
+/// devices between attempts
+///
+fn retry<T>(
+    hid_api: &mut HidApi,
+    attempts: u32,
+    delay: Duration,
+    open: fn(&HidApi) -> Result<T, error::Error>,
+) -> Result<T, error::Error> {
+    let mut attempt = 1;
+    loop {
+        match open(hid_api) {
+            Ok(ctlr) => return Ok(ctlr),
+            Err(err) if attempt >= attempts => return Err(err),
+            Err(_) => {
+                attempt += 1;
+                thread::sleep(delay);
+                hid_api.refresh_devices()?;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,170 @@
+use crate::display::{glyph_width, Canvas, Font, Pixel, GLYPH_OFFSET};
+
+// Default blank space between the end of the text and its start when wrapping around
+const DEFAULT_GAP: usize = 16;
+
+///
+/// Text ticker that scrolls a line of text across a text row
+///
+/// Text that fits within the canvas is drawn from the left edge without scrolling, wider text
+/// moves left by `step` pixels on each call to `tick` and wraps around after a gap.
+///
+/// ```
+/// use maschine::{fonts, Marquee, MonochromeCanvas};
+///
+/// let mut canvas = MonochromeCanvas::new(128, 64);
+/// let mut marquee = Marquee::new("Now playing: a track with a long name", 0, &fonts::FONT_NX5);
+///
+/// marquee.render(&mut canvas);
+/// marquee.tick();
+/// marquee.render(&mut canvas);
+/// ```
+///
+pub struct Marquee {
+    text: String,
+    row: usize,
+    font: &'static Font,
+    step: usize,
+    gap: usize,
+    offset: usize,
+    columns: Vec<u8>,
+}
+
+impl Marquee {
+    pub fn new(text: &str, row: usize, font: &'static Font) -> Self {
+        let mut marquee = Marquee {
+            text: String::new(),
+            row,
+            font,
+            step: 1,
+            gap: DEFAULT_GAP,
+            offset: 0,
+            columns: Vec::new(),
+        };
+        marquee.set_text(text);
+        marquee
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    ///
+    /// Replace the text, scrolling restarts from the beginning
+    ///
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.offset = 0;
+
+        // Pixel columns of the text with a blank column between characters
+        self.columns.clear();
+        for c in text.chars() {
+            let width = glyph_width(c, self.font);
+            if width > 0 {
+                let (_, glyph) = self.font[c as usize - 0x20];
+                self.columns.extend_from_slice(&glyph[..width]);
+                self.columns.push(0);
+            }
+        }
+        self.columns.pop();
+    }
+
+    ///
+    /// Set the number of pixels the text moves on each tick (defaults to 1)
+    ///
+    pub fn set_step(&mut self, step: usize) {
+        self.step = step;
+    }
+
+    ///
+    /// Set the blank space in pixels between the end of the text and its start (defaults to 16)
+    ///
+    pub fn set_gap(&mut self, gap: usize) {
+        self.gap = gap;
+    }
+
+    ///
+    /// Width of the text in pixels
+    ///
+    pub fn text_width(&self) -> usize {
+        self.columns.len()
+    }
+
+    ///
+    /// Advance the text
+    ///
+    pub fn tick(&mut self) {
+        let length = self.columns.len() + self.gap;
+        if length > 0 {
+            self.offset = (self.offset + self.step) % length;
+        }
+    }
+
+    ///
+    /// Draw the current position of the text, replacing the contents of the row
+    ///
+    pub fn render(&self, canvas: &mut dyn Canvas<Pixel>) {
+        canvas.fill_row(self.row, Pixel::Off);
+
+        let width = canvas.width();
+        let scrolling = self.columns.len() > width;
+        let length = self.columns.len() + self.gap;
+        for x in 0..width {
+            let column = if scrolling {
+                (self.offset + x) % length
+            } else {
+                x
+            };
+            let Some(bits) = self.columns.get(column) else {
+                continue;
+            };
+            for bit in 0..8 {
+                if bits & (1 << bit) != 0 {
+                    canvas.set_pixel(x, (self.row << 3) + GLYPH_OFFSET + bit, Pixel::On);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::MonochromeCanvas;
+    use crate::fonts::FONT_NX5;
+
+    #[test]
+    fn narrow_text_does_not_scroll() {
+        let mut canvas = MonochromeCanvas::new(16, 8);
+        let mut expected = MonochromeCanvas::new(16, 8);
+        expected.print("!!", 0, 0, &FONT_NX5, Pixel::On);
+
+        let mut marquee = Marquee::new("!!", 0, &FONT_NX5);
+        assert_eq!(marquee.text_width(), 3);
+        marquee.tick();
+        marquee.render(&mut canvas);
+
+        assert_eq!(canvas.data(), expected.data());
+    }
+
+    #[test]
+    fn wide_text_scrolls_and_wraps() {
+        let mut canvas = MonochromeCanvas::new(4, 8);
+        let mut marquee = Marquee::new("!!!!", 0, &FONT_NX5);
+        marquee.set_gap(2);
+
+        marquee.render(&mut canvas);
+        assert_eq!(canvas.data(), &[0x5C, 0x00, 0x5C, 0x00]);
+
+        marquee.tick();
+        marquee.render(&mut canvas);
+        assert_eq!(canvas.data(), &[0x00, 0x5C, 0x00, 0x5C]);
+
+        // Text is 7 columns plus a gap of 2, so 9 ticks return to the start
+        for _ in 0..8 {
+            marquee.tick();
+        }
+        marquee.render(&mut canvas);
+        assert_eq!(canvas.data(), &[0x5C, 0x00, 0x5C, 0x00]);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::events::{Direction, Event, EventHandler};
+use midir::{MidiOutputConnection, SendError};
+
+const NOTE_OFF: u8 = 0x80;
+const NOTE_ON: u8 = 0x90;
+const POLY_PRESSURE: u8 = 0xA0;
+const CONTROL_CHANGE: u8 = 0xB0;
+
+/// First pad note, C1 matches the General MIDI drum map
+const DEFAULT_BASE_NOTE: u8 = 36;
+const DEFAULT_ENCODER_CC: u8 = 16;
+const DEFAULT_BUTTON_CC: u8 = 64;
+
+/// Centre value of a relative (offset binary) controller
+const RELATIVE_CENTRE: u8 = 64;
+
+///
+/// Maps controller events to MIDI messages
+///
+/// - Pads are sent as Note On/Off with velocity (and polyphonic pressure for aftertouch)
+/// - Encoders are sent as relative CC values centred on 64
+/// - Buttons are sent as CC values of 127 (pressed) or 0 (released)
+///
+#[derive(Debug, Clone)]
+pub struct MidiMapper {
+    channel: u8,
+    pad_notes: [u8; 16],
+    encoder_cc: u8,
+    button_cc: u8,
+}
+
+impl Default for MidiMapper {
+    fn default() -> Self {
+        let mut pad_notes = [0; 16];
+        for (idx, note) in pad_notes.iter_mut().enumerate() {
+            *note = DEFAULT_BASE_NOTE + idx as u8;
+        }
+
+        MidiMapper {
+            channel: 0,
+            pad_notes,
+            encoder_cc: DEFAULT_ENCODER_CC,
+            button_cc: DEFAULT_BUTTON_CC,
+        }
+    }
+}
+
+impl MidiMapper {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the MIDI channel (0-15) messages are sent on
+    pub fn set_channel(&mut self, channel: u8) {
+        self.channel = channel & 0x0F;
+    }
+
+    /// Set the note sent by a pad
+    pub fn set_pad_note(&mut self, pad: u8, note: u8) {
+        if let Some(entry) = self.pad_notes.get_mut(pad as usize) {
+            *entry = note & 0x7F;
+        }
+    }
+
+    /// Replace the complete pad to note table
+    pub fn set_pad_notes(&mut self, notes: [u8; 16]) {
+        self.pad_notes = notes.map(|note| note & 0x7F);
+    }
+
+    /// Note sent by a pad
+    pub fn pad_note(&self, pad: u8) -> Option<u8> {
+        self.pad_notes.get(pad as usize).copied()
+    }
+
+    /// Set the controller number used for the encoder
+    pub fn set_encoder_cc(&mut self, cc: u8) {
+        self.encoder_cc = cc & 0x7F;
+    }
+
+    /// Set the first controller number used for buttons, buttons are numbered from this
+    pub fn set_button_cc(&mut self, cc: u8) {
+        self.button_cc = cc & 0x7F;
+    }
+
+    ///
+    /// Translate an event into MIDI messages
+    ///
+    /// Events without a MIDI mapping produce no messages.
+    ///
+    pub fn map(&self, event: &Event) -> Vec<[u8; 3]> {
+        match event {
+            Event::Pad(pad, velocity, _) => self.note_on(*pad, *velocity).into_iter().collect(),
+            Event::PadChord(pads, _) => pads
+                .iter()
+                .filter_map(|(pad, velocity)| self.note_on(*pad, *velocity))
+                .collect(),
+            Event::PadRelease(pad, _) => self
+                .pad_note(*pad)
+                .map(|note| [NOTE_OFF | self.channel, note, 0])
+                .into_iter()
+                .collect(),
+            Event::PadAftertouch(pad, pressure, _) => self
+                .pad_note(*pad)
+                .map(|note| [POLY_PRESSURE | self.channel, note, (*pressure >> 5) as u8])
+                .into_iter()
+                .collect(),
+            Event::Encoder(_, direction, steps, _) => {
+                let steps = (*steps).min(RELATIVE_CENTRE - 1);
+                let value = match direction {
+                    Direction::Up => RELATIVE_CENTRE + steps,
+                    Direction::Down => RELATIVE_CENTRE - steps,
+                };
+                vec![[CONTROL_CHANGE | self.channel, self.encoder_cc, value]]
+            }
+            Event::Button(button, pressed, _) => {
+                let cc = self.button_cc.saturating_add(*button as u8).min(0x7F);
+                let value = if *pressed { 0x7F } else { 0x00 };
+                vec![[CONTROL_CHANGE | self.channel, cc, value]]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    ///
+    /// Send the MIDI messages for an event
+    ///
+    /// Returns `true` if any messages were sent.
+    ///
+    pub fn send(
+        &self,
+        connection: &mut MidiOutputConnection,
+        event: &Event,
+    ) -> Result<bool, SendError> {
+        let messages = self.map(event);
+        for message in messages.iter() {
+            connection.send(message)?;
+        }
+        Ok(!messages.is_empty())
+    }
+
+    fn note_on(&self, pad: u8, velocity: u8) -> Option<[u8; 3]> {
+        // Scale the 8bit pad velocity to 7bit, a Note On with zero velocity is treated as a
+        // Note Off
+        self.pad_note(pad)
+            .map(|note| [NOTE_ON | self.channel, note, (velocity >> 1).max(1)])
+    }
+}
+
+///
+/// Event handler that forwards events to a MIDI output
+///
+pub struct MidiHandler {
+    pub mapper: MidiMapper,
+    connection: MidiOutputConnection,
+}
+
+impl MidiHandler {
+    pub fn new(mapper: MidiMapper, connection: MidiOutputConnection) -> Self {
+        MidiHandler { mapper, connection }
+    }
+
+    /// Release the MIDI output connection
+    pub fn into_connection(self) -> MidiOutputConnection {
+        self.connection
+    }
+}
+
+impl EventHandler for MidiHandler {
+    fn handle(&mut self, event: &Event) -> bool {
+        self.mapper
+            .send(&mut self.connection, event)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Button;
+
+    #[test]
+    fn pads_map_to_notes() {
+        let mut mapper = MidiMapper::new();
+        mapper.set_channel(2);
+
+        assert_eq!(mapper.map(&Event::Pad(0, 100, false)), vec![[0x92, 36, 50]]);
+        assert_eq!(
+            mapper.map(&Event::PadRelease(15, false)),
+            vec![[0x82, 51, 0]]
+        );
+        assert!(mapper.map(&Event::Pad(16, 100, false)).is_empty());
+
+        mapper.set_pad_note(0, 60);
+        assert_eq!(
+            mapper.map(&Event::PadChord(vec![(0, 0), (1, 200), (2, 255)], false)),
+            vec![[0x92, 60, 1], [0x92, 37, 100], [0x92, 38, 127]]
+        );
+    }
+
+    #[test]
+    fn encoder_and_buttons_map_to_control_change() {
+        let mapper = MidiMapper::new();
+
+        assert_eq!(
+            mapper.map(&Event::Encoder(0, Direction::Up, 2, false)),
+            vec![[0xB0, 16, 66]]
+        );
+        assert_eq!(
+            mapper.map(&Event::Encoder(0, Direction::Down, 1, false)),
+            vec![[0xB0, 16, 63]]
+        );
+        assert_eq!(
+            mapper.map(&Event::Button(Button::Play, true, false)),
+            vec![[0xB0, 66, 127]]
+        );
+        assert!(mapper
+            .map(&Event::ButtonLongPress(Button::Play, false))
+            .is_empty());
+    }
+}
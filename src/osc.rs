@@ -0,0 +1,220 @@
+use crate::events::{Direction, Event, EventHandler};
+use rosc::{encoder, OscMessage, OscPacket, OscType};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+/// Placeholder replaced by a pad or encoder number or button name in an address pattern
+const PLACEHOLDER: &str = "{}";
+
+const DEFAULT_PAD_ADDRESS: &str = "/maschine/pad/{}";
+const DEFAULT_AFTERTOUCH_ADDRESS: &str = "/maschine/pad/{}/pressure";
+const DEFAULT_ENCODER_ADDRESS: &str = "/maschine/encoder";
+const DEFAULT_BUTTON_ADDRESS: &str = "/maschine/button/{}";
+
+///
+/// Maps controller events to OSC messages
+///
+/// - Pads are sent to the pad address with the velocity, releases are sent with a velocity of 0
+/// - Aftertouch is sent to the aftertouch address with the 12bit pressure
+/// - Encoders are sent to the encoder address with the signed number of steps turned
+/// - Buttons are sent to the button address with 1 (pressed) or 0 (released)
+///
+/// In address patterns `{}` is replaced by the pad or encoder number, or the lower case button
+/// name.
+///
+#[derive(Debug, Clone)]
+pub struct OscMapper {
+    pad_address: String,
+    aftertouch_address: String,
+    encoder_address: String,
+    button_address: String,
+}
+
+impl Default for OscMapper {
+    fn default() -> Self {
+        OscMapper {
+            pad_address: DEFAULT_PAD_ADDRESS.to_string(),
+            aftertouch_address: DEFAULT_AFTERTOUCH_ADDRESS.to_string(),
+            encoder_address: DEFAULT_ENCODER_ADDRESS.to_string(),
+            button_address: DEFAULT_BUTTON_ADDRESS.to_string(),
+        }
+    }
+}
+
+impl OscMapper {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the address pattern pad strikes and releases are sent to
+    pub fn set_pad_address(&mut self, pattern: &str) {
+        self.pad_address = pattern.to_string();
+    }
+
+    /// Set the address pattern pad aftertouch is sent to
+    pub fn set_aftertouch_address(&mut self, pattern: &str) {
+        self.aftertouch_address = pattern.to_string();
+    }
+
+    /// Set the address pattern encoder turns are sent to
+    pub fn set_encoder_address(&mut self, pattern: &str) {
+        self.encoder_address = pattern.to_string();
+    }
+
+    /// Set the address pattern button changes are sent to
+    pub fn set_button_address(&mut self, pattern: &str) {
+        self.button_address = pattern.to_string();
+    }
+
+    ///
+    /// Translate an event into OSC messages
+    ///
+    /// Events without an OSC mapping produce no messages.
+    ///
+    pub fn map(&self, event: &Event) -> Vec<OscMessage> {
+        match event {
+            Event::Pad(pad, velocity, _) => vec![self.pad_message(*pad, *velocity)],
+            Event::PadChord(pads, _) => pads
+                .iter()
+                .map(|(pad, velocity)| self.pad_message(*pad, *velocity))
+                .collect(),
+            Event::PadRelease(pad, _) => vec![self.pad_message(*pad, 0)],
+            Event::PadAftertouch(pad, pressure, _) => vec![message(
+                &self.aftertouch_address,
+                &pad.to_string(),
+                *pressure as i32,
+            )],
+            Event::Encoder(encoder, direction, steps, _) => {
+                let delta = match direction {
+                    Direction::Up => *steps as i32,
+                    Direction::Down => -(*steps as i32),
+                };
+                vec![message(&self.encoder_address, &encoder.to_string(), delta)]
+            }
+            Event::Button(button, pressed, _) => {
+                let name = format!("{:?}", button).to_lowercase();
+                vec![message(&self.button_address, &name, *pressed as i32)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn pad_message(&self, pad: u8, velocity: u8) -> OscMessage {
+        message(&self.pad_address, &pad.to_string(), velocity as i32)
+    }
+}
+
+/// Build a message with a single integer argument from an address pattern
+fn message(pattern: &str, name: &str, value: i32) -> OscMessage {
+    OscMessage {
+        addr: pattern.replace(PLACEHOLDER, name),
+        args: vec![OscType::Int(value)],
+    }
+}
+
+///
+/// Event handler that sends events as OSC messages over UDP
+///
+pub struct OscSink {
+    pub mapper: OscMapper,
+    socket: UdpSocket,
+    target: SocketAddr,
+}
+
+impl OscSink {
+    ///
+    /// Create a sink sending to the target address from an ephemeral local port
+    ///
+    pub fn new<A: ToSocketAddrs>(mapper: OscMapper, target: A) -> io::Result<Self> {
+        let target = target
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No target address"))?;
+        let local: SocketAddr = if target.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+
+        Ok(OscSink {
+            mapper,
+            socket: UdpSocket::bind(local)?,
+            target,
+        })
+    }
+
+    ///
+    /// Send the OSC messages for an event
+    ///
+    /// Returns `true` if any messages were sent.
+    ///
+    pub fn send(&self, event: &Event) -> io::Result<bool> {
+        let messages = self.mapper.map(event);
+        for message in messages.iter() {
+            let packet = encoder::encode(&OscPacket::Message(message.clone()))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{:?}", e)))?;
+            self.socket.send_to(&packet, self.target)?;
+        }
+        Ok(!messages.is_empty())
+    }
+}
+
+impl EventHandler for OscSink {
+    fn handle(&mut self, event: &Event) -> bool {
+        self.send(event).unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Button;
+    use rosc::decoder;
+
+    #[test]
+    fn map_pads_and_buttons() {
+        let mapper = OscMapper::new();
+
+        let messages = mapper.map(&Event::Pad(3, 100, false));
+        assert_eq!(messages[0].addr, "/maschine/pad/3");
+        assert_eq!(messages[0].args, vec![OscType::Int(100)]);
+
+        let messages = mapper.map(&Event::Button(Button::Play, true, false));
+        assert_eq!(messages[0].addr, "/maschine/button/play");
+        assert_eq!(messages[0].args, vec![OscType::Int(1)]);
+
+        let messages = mapper.map(&Event::Encoder(0, Direction::Down, 2, false));
+        assert_eq!(messages[0].addr, "/maschine/encoder");
+        assert_eq!(messages[0].args, vec![OscType::Int(-2)]);
+
+        assert!(mapper.map(&Event::PadDoubleTap(3, 100, false)).is_empty());
+    }
+
+    #[test]
+    fn custom_address_pattern() {
+        let mut mapper = OscMapper::new();
+        mapper.set_pad_address("/drums/{}/hit");
+
+        let messages = mapper.map(&Event::PadRelease(7, false));
+        assert_eq!(messages[0].addr, "/drums/7/hit");
+        assert_eq!(messages[0].args, vec![OscType::Int(0)]);
+    }
+
+    #[test]
+    fn sink_sends_udp_packets() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut sink = OscSink::new(OscMapper::new(), receiver.local_addr().unwrap()).unwrap();
+
+        assert!(sink.handle(&Event::Pad(1, 64, false)));
+
+        let mut buffer = [0u8; 256];
+        let size = receiver.recv(&mut buffer).unwrap();
+        match decoder::decode_udp(&buffer[..size]).unwrap().1 {
+            OscPacket::Message(message) => {
+                assert_eq!(message.addr, "/maschine/pad/1");
+                assert_eq!(message.args, vec![OscType::Int(64)]);
+            }
+            packet => panic!("Unexpected packet {:?}", packet),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//!
+//! # PNG loader
+//!
+//! Decodes 8bit (or lower bit depth and palette images, which are expanded) Greyscale, RGB and
+//! RGBA PNG images into the same colour buffer used by the bitmap loader. Any alpha channel
+//! is ignored.
+//!
+//! Monochrome canvases can also be saved as PNG images to preview a display off device.
+
+use crate::bitmap::Error;
+use crate::colour::Colour;
+use crate::display::{Canvas, MonochromeCanvas, Pixel};
+use crate::image::Image;
+use ::png::{BitDepth, ColorType, Decoder, Encoder, EncodingError, Transformations};
+use std::fs::File;
+use std::io::{BufWriter, Cursor, Write};
+use std::path::Path;
+
+impl From<::png::DecodingError> for Error {
+    fn from(err: ::png::DecodingError) -> Error {
+        Error::PngDecoding(Box::new(err))
+    }
+}
+
+pub struct Png {
+    width: usize,
+    height: usize,
+    pixel_buffer: Vec<Colour>,
+}
+
+impl Png {
+    pub fn read_from_buffer(buffer: Vec<u8>) -> Result<Self, Error> {
+        let mut decoder = Decoder::new(Cursor::new(buffer));
+        decoder.set_transformations(Transformations::EXPAND | Transformations::STRIP_16);
+        let mut reader = decoder.read_info()?;
+
+        let mut data = vec![0u8; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data)?;
+
+        let channels = match info.color_type {
+            ColorType::Grayscale => 1,
+            ColorType::GrayscaleAlpha => 2,
+            ColorType::Rgb => 3,
+            ColorType::Rgba => 4,
+            _ => return Err(Error::UnsupportedColourType),
+        };
+
+        let width = info.width as usize;
+        let height = info.height as usize;
+        let mut pixel_buffer: Vec<Colour> = Vec::with_capacity(width * height);
+        for row in data.chunks(info.line_size).take(height) {
+            for pixel in row.chunks(channels).take(width) {
+                pixel_buffer.push(if channels < 3 {
+                    Colour::new(pixel[0], pixel[0], pixel[0])
+                } else {
+                    Colour::new(pixel[0], pixel[1], pixel[2])
+                });
+            }
+        }
+
+        Ok(Png {
+            width,
+            height,
+            pixel_buffer,
+        })
+    }
+}
+
+impl Image for Png {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> Colour {
+        self.pixel_buffer[(y * self.width) + x]
+    }
+}
+
+impl MonochromeCanvas {
+    ///
+    /// Write the canvas as an 8bit greyscale PNG, On pixels are white and Off pixels black
+    ///
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<(), EncodingError> {
+        let mut encoder = Encoder::new(writer, self.width() as u32, self.height() as u32);
+        encoder.set_color(ColorType::Grayscale);
+        encoder.set_depth(BitDepth::Eight);
+
+        let mut data = Vec::with_capacity(self.width() * self.height());
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                data.push(match self.pixel(x, y) {
+                    Some(Pixel::On) => 0xFF,
+                    _ => 0x00,
+                });
+            }
+        }
+
+        encoder.write_header()?.write_image_data(&data)
+    }
+
+    ///
+    /// Save the canvas to a PNG file, see `write_png`
+    ///
+    pub fn save_png(&self, path: &Path) -> Result<(), EncodingError> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(width: u32, height: u32, colour_type: ColorType, data: &[u8]) -> Vec<u8> {
+        let mut buffer = vec![];
+        {
+            let mut encoder = Encoder::new(&mut buffer, width, height);
+            encoder.set_color(colour_type);
+            encoder.set_depth(BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(data).unwrap();
+        }
+        buffer
+    }
+
+    #[test]
+    fn read_rgb() {
+        let buffer = encode(2, 1, ColorType::Rgb, &[0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF]);
+        let png = Png::read_from_buffer(buffer).unwrap();
+
+        assert_eq!((png.width(), png.height()), (2, 1));
+        assert_eq!(png.pixel(0, 0).components().0, 0xFF);
+        assert_eq!(png.pixel(1, 0).components().2, 0xFF);
+        assert_eq!(png.pixel(1, 0).components().0, 0x00);
+    }
+
+    #[test]
+    fn read_rgba_and_greyscale() {
+        let buffer = encode(1, 2, ColorType::Rgba, &[0, 0xFF, 0, 0x10, 1, 2, 3, 0xFF]);
+        let png = Png::read_from_buffer(buffer).unwrap();
+
+        assert_eq!(png.pixel(0, 0).components().1, 0xFF);
+        assert_eq!(png.pixel(0, 1).components().2, 3);
+
+        let buffer = encode(1, 1, ColorType::Grayscale, &[0x80]);
+        let png = Png::read_from_buffer(buffer).unwrap();
+
+        assert_eq!(png.pixel(0, 0).luminance(), 0x80);
+    }
+
+    #[test]
+    fn write_canvas() {
+        let mut canvas = MonochromeCanvas::new(128, 64);
+        canvas.set_pixel(0, 0, Pixel::On);
+        canvas.set_pixel(127, 9, Pixel::On);
+
+        let mut buffer = vec![];
+        canvas.write_png(&mut buffer).unwrap();
+        let png = Png::read_from_buffer(buffer).unwrap();
+
+        assert_eq!((png.width(), png.height()), (128, 64));
+        assert_eq!(png.pixel(0, 0).luminance(), 0xFF);
+        assert_eq!(png.pixel(127, 9).luminance(), 0xFF);
+        assert_eq!(png.pixel(1, 0).luminance(), 0x00);
+        assert_eq!(png.pixel(127, 8).luminance(), 0x00);
+    }
+}
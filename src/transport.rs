@@ -0,0 +1,166 @@
+use crate::colour::Colour;
+use crate::controller::Controller;
+use crate::events::{Button, Event};
+
+///
+/// Transport action triggered by the transport buttons
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// Playback started
+    Play,
+
+    /// Playback (and recording) stopped
+    Stop,
+
+    /// Recording toggled, see `TransportState::is_recording`
+    Record,
+
+    /// Return to the start
+    Return,
+}
+
+///
+/// Transport section driven by the Play, Rec and Restart buttons
+///
+/// Play toggles between playing and stopped, Rec toggles recording and Restart returns to the
+/// start without changing the running state. The button LEDs are lit to match.
+///
+/// ```
+/// use maschine::devices::MockController;
+/// use maschine::{Button, Event, Transport, TransportState};
+///
+/// let mut ctlr = MockController::new();
+/// let mut transport = TransportState::new();
+///
+/// let action = transport.process(&Event::Button(Button::Play, true, false), &mut ctlr);
+/// assert_eq!(action, Some(Transport::Play));
+/// assert!(transport.is_playing());
+/// ```
+///
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TransportState {
+    playing: bool,
+    recording: bool,
+}
+
+impl TransportState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    ///
+    /// Interpret an event, returns the transport action triggered by a transport button press
+    ///
+    pub fn handle_event(&mut self, event: &Event) -> Option<Transport> {
+        match event {
+            Event::Button(Button::Play, true, _) => {
+                self.playing = !self.playing;
+                if self.playing {
+                    Some(Transport::Play)
+                } else {
+                    self.recording = false;
+                    Some(Transport::Stop)
+                }
+            }
+            Event::Button(Button::Rec, true, _) => {
+                self.recording = !self.recording;
+                Some(Transport::Record)
+            }
+            Event::Button(Button::Restart, true, _) => Some(Transport::Return),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Light the Play and Rec LEDs to match the running state
+    ///
+    pub fn update_leds<C: Controller + ?Sized>(&self, controller: &mut C) {
+        controller.set_button_led(Button::Play, led_colour(self.playing));
+        controller.set_button_led(Button::Rec, led_colour(self.recording));
+    }
+
+    ///
+    /// Interpret an event and update the LEDs of a controller when a transport action occurs
+    ///
+    pub fn process<C: Controller + ?Sized>(
+        &mut self,
+        event: &Event,
+        controller: &mut C,
+    ) -> Option<Transport> {
+        let action = self.handle_event(event);
+        if action.is_some() {
+            self.update_leds(controller);
+        }
+        action
+    }
+}
+
+fn led_colour(on: bool) -> Colour {
+    if on {
+        Colour::WHITE
+    } else {
+        Colour::BLACK
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devices::MockController;
+
+    fn press(button: Button) -> Event {
+        Event::Button(button, true, false)
+    }
+
+    #[test]
+    fn play_toggles_and_stop_ends_recording() {
+        let mut ctlr = MockController::new();
+        let mut transport = TransportState::new();
+
+        assert_eq!(
+            transport.process(&press(Button::Play), &mut ctlr),
+            Some(Transport::Play)
+        );
+        assert_eq!(
+            transport.process(&press(Button::Rec), &mut ctlr),
+            Some(Transport::Record)
+        );
+        assert!(transport.is_recording());
+        assert_eq!(ctlr.button_led(Button::Play), Colour::WHITE);
+        assert_eq!(ctlr.button_led(Button::Rec), Colour::WHITE);
+
+        assert_eq!(
+            transport.process(&press(Button::Play), &mut ctlr),
+            Some(Transport::Stop)
+        );
+        assert!(!transport.is_playing());
+        assert!(!transport.is_recording());
+        assert_eq!(ctlr.button_led(Button::Play), Colour::BLACK);
+        assert_eq!(ctlr.button_led(Button::Rec), Colour::BLACK);
+    }
+
+    #[test]
+    fn restart_returns_and_other_events_ignored() {
+        let mut transport = TransportState::new();
+
+        assert_eq!(
+            transport.handle_event(&press(Button::Restart)),
+            Some(Transport::Return)
+        );
+        assert!(!transport.is_playing());
+        assert_eq!(
+            transport.handle_event(&Event::Button(Button::Play, false, false)),
+            None
+        );
+        assert_eq!(transport.handle_event(&Event::Pad(0, 100, false)), None);
+    }
+}
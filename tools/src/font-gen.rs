@@ -1,24 +1,18 @@
 ///
 /// Simple tool to parse a 160x24 pixel grid into a font glyph lookup table
 ///
-/// All Glyphs are formatted for easy insertion into a data table
+/// All Glyphs are formatted for easy insertion into a data table, the grid can be either a
+/// bitmap or a PNG image.
 ///
 use clap::{AppSettings, Clap};
+use maschine::bitmap::{self, Image};
+use maschine::png::Png;
+use maschine::Colour;
 use std::fs::File;
-use std::io::Read;
+use std::io::{stdout, Read, Write};
 
-pub mod bitmap;
-
-const WHITE: bitmap::Colour = bitmap::Colour {
-    red: 0xFF,
-    green: 0xFF,
-    blue: 0xFF,
-};
-const BLACK: bitmap::Colour = bitmap::Colour {
-    red: 0x00,
-    green: 0x00,
-    blue: 0x00,
-};
+const WHITE: Colour = Colour::WHITE;
+const BLACK: Colour = Colour::BLACK;
 
 struct Glyph {
     width: usize,
@@ -27,7 +21,7 @@ struct Glyph {
 
 impl Glyph {
     fn from_bitmap(
-        bitmap: &bitmap::Bitmap,
+        bitmap: &dyn Image,
         x_offset: usize,
         y_offset: usize,
         width: usize,
@@ -58,12 +52,12 @@ impl Glyph {
     }
 }
 
-fn generate_glyphs(bm: bitmap::Bitmap, glyph_width: usize, glyph_height: usize) -> Vec<Glyph> {
+fn generate_glyphs(bm: &dyn Image, glyph_width: usize, glyph_height: usize) -> Vec<Glyph> {
     let mut glyphs: Vec<Glyph> = Vec::new();
     for y in 0..(bm.height() / glyph_height) {
         for x in 0..(bm.width() / glyph_width) {
             glyphs.push(Glyph::from_bitmap(
-                &bm,
+                bm,
                 x * glyph_width,
                 y * glyph_height,
                 glyph_width,
@@ -83,33 +77,64 @@ struct Opts {
     width: usize,
     #[clap(short, long, default_value = "5")]
     height: usize,
+    /// File to write the font table to, defaults to stdout
+    #[clap(short, long)]
+    output: Option<String>,
+    /// Name of the font constant
+    #[clap(long, default_value = "FONT")]
+    name: String,
+    /// Codepoint of the first glyph (decimal or 0x prefixed hex)
+    #[clap(long, default_value = "0x20", parse(try_from_str = parse_codepoint))]
+    start: u32,
+    /// Number of glyphs to generate, defaults to every glyph in the grid
+    #[clap(long)]
+    count: Option<usize>,
+}
+
+fn parse_codepoint(value: &str) -> Result<u32, std::num::ParseIntError> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => value.parse(),
+    }
 }
 
 fn main() -> Result<(), bitmap::Error> {
     let opts: Opts = Opts::parse();
 
     let mut buffer: Vec<u8> = Vec::new();
-    let mut file = File::open(opts.file_path)?;
+    let mut file = File::open(&opts.file_path)?;
     file.read_to_end(&mut buffer)?;
 
-    let glyphs = generate_glyphs(
-        bitmap::Bitmap::read_from_buffer(buffer)?,
-        opts.width,
-        opts.height,
-    );
+    let image: Box<dyn Image> = if opts.file_path.to_lowercase().ends_with(".png") {
+        Box::new(Png::read_from_buffer(buffer)?)
+    } else {
+        Box::new(bitmap::Bitmap::read_from_buffer(buffer)?)
+    };
+    let mut glyphs = generate_glyphs(image.as_ref(), opts.width, opts.height);
+    if let Some(count) = opts.count {
+        glyphs.truncate(count);
+    }
+
+    let mut out: Box<dyn Write> = match &opts.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(stdout()),
+    };
 
-    println!(
-        "pub const FONT: [(u8, [u8; {}]); {}] = [",
+    writeln!(
+        out,
+        "pub const {}: [(u8, [u8; {}]); {}] = [",
+        opts.name,
         opts.width,
         glyphs.len()
-    );
+    )?;
     for idx in 0..glyphs.len() {
         let mut slices = glyphs[idx].data.clone();
         for _ in 0..(opts.width - glyphs[idx].width) {
             slices.push(0);
         }
 
-        println!(
+        writeln!(
+            out,
             "    ({}, [{}]),  // {}",
             glyphs[idx].width,
             slices
@@ -117,10 +142,10 @@ fn main() -> Result<(), bitmap::Error> {
                 .map(|c| format!("{}", c))
                 .collect::<Vec<String>>()
                 .join(", "),
-            ((0x20 + idx) as u8) as char
-        );
+            char::from_u32(opts.start + idx as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
+        )?;
     }
-    println!("];");
+    writeln!(out, "];")?;
 
     Ok(())
 }